@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use tauri::ipc::Response;
+use tauri::State;
+
+use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::types::asset::{AssetId, AssetInfo, AssetKind};
+use crate::types::error::AppResult;
+
+/// Embed the file at `path` in the project, to be saved in its bundle.
+#[tauri::command]
+pub fn import_asset(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    kind: AssetKind,
+    path: String,
+) -> AppResult<AssetInfo> {
+    documents.with(&document_id, |project| {
+        engine::assets::import(project, kind, Path::new(&path))
+    })
+}
+
+#[tauri::command]
+pub fn list_assets(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<Vec<AssetInfo>> {
+    documents.with(&document_id, |project| Ok(engine::assets::list(project)))
+}
+
+/// An asset's contents, sent as raw bytes rather than a JSON array.
+#[tauri::command]
+pub fn get_asset_data(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: AssetId,
+) -> AppResult<Response> {
+    let data = documents.with(&document_id, |project| engine::assets::data(project, &id))?;
+    Ok(Response::new(data))
+}
+
+#[tauri::command]
+pub fn remove_asset(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: AssetId,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| engine::assets::remove(project, &id))
+}
@@ -0,0 +1,19 @@
+use tauri::State;
+
+use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::types::batch::BatchOp;
+use crate::types::error::AppResult;
+
+/// Apply several piece and seam mutations atomically, as one undo step labelled `label`.
+#[tauri::command]
+pub fn apply_batch(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    ops: Vec<BatchOp>,
+    label: Option<String>,
+) -> AppResult<Vec<Option<String>>> {
+    documents.with(&document_id, |project| {
+        engine::batch::apply_batch(project, ops, label)
+    })
+}
@@ -0,0 +1,61 @@
+use tauri::State;
+
+use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::types::checkpoint::{CheckpointDiff, CheckpointId, CheckpointInfo};
+use crate::types::error::AppResult;
+
+#[tauri::command]
+pub fn create_checkpoint(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    name: String,
+) -> AppResult<CheckpointId> {
+    documents.with(&document_id, |project| {
+        engine::checkpoint::create(project, name)
+    })
+}
+
+#[tauri::command]
+pub fn list_checkpoints(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<Vec<CheckpointInfo>> {
+    documents.with(&document_id, |project| {
+        Ok(engine::checkpoint::list(project))
+    })
+}
+
+#[tauri::command]
+pub fn delete_checkpoint(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: CheckpointId,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| {
+        engine::checkpoint::delete(project, &id)
+    })
+}
+
+#[tauri::command]
+pub fn compare_checkpoint(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: CheckpointId,
+) -> AppResult<CheckpointDiff> {
+    documents.with(&document_id, |project| {
+        engine::checkpoint::compare(project, &id)
+    })
+}
+
+/// Return every piece and seam to a checkpoint, as one undoable step.
+#[tauri::command]
+pub fn restore_checkpoint(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: CheckpointId,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| {
+        engine::checkpoint::restore(project, &id)
+    })
+}
@@ -0,0 +1,34 @@
+use tauri::State;
+
+use crate::engine::document::{self, DocumentId, Documents};
+use crate::types::error::AppResult;
+use crate::types::pattern::PatternPieceId;
+
+/// Open a new, empty document, e.g. for a new window. Returns its id, which every other
+/// project command takes.
+#[tauri::command]
+pub fn open_document(documents: State<'_, Documents>) -> AppResult<DocumentId> {
+    documents.open()
+}
+
+#[tauri::command]
+pub fn close_document(documents: State<'_, Documents>, document_id: DocumentId) -> AppResult<()> {
+    documents.close(&document_id)
+}
+
+/// Copy pieces, and the seams between them, from one document into another as one undo
+/// step there. Returns the ids of the new pieces.
+#[tauri::command]
+pub fn copy_pieces(
+    documents: State<'_, Documents>,
+    from_document_id: DocumentId,
+    to_document_id: DocumentId,
+    piece_ids: Vec<PatternPieceId>,
+) -> AppResult<Vec<PatternPieceId>> {
+    let (pieces, seams) = documents.with(&from_document_id, |project| {
+        document::copy_pieces(project, &piece_ids)
+    })?;
+    documents.with(&to_document_id, |project| {
+        document::paste_pieces(project, pieces, seams)
+    })
+}
@@ -1,36 +1,70 @@
+use tauri::State;
+
+use crate::engine::document::{DocumentId, Documents};
 use crate::engine::export::{self, PaperSize};
-use crate::engine::pattern_piece;
+use crate::engine::{fold, pattern_piece};
+use crate::types::error::AppResult;
 
+/// The pieces to export, with cut-on-fold pieces unfolded to their full shape.
 fn get_pieces_by_ids(
+    documents: &Documents,
+    document_id: &DocumentId,
     piece_ids: &[String],
-) -> Result<Vec<crate::types::pattern::PatternPieceData>, String> {
-    if piece_ids.is_empty() {
-        return pattern_piece::get_all();
-    }
-    piece_ids.iter().map(pattern_piece::get).collect()
+) -> AppResult<Vec<crate::types::pattern::PatternPieceData>> {
+    let pieces = documents.with(document_id, |project| {
+        if piece_ids.is_empty() {
+            Ok(pattern_piece::get_all(project))
+        } else {
+            piece_ids
+                .iter()
+                .map(|id| pattern_piece::get(project, id))
+                .collect::<Result<Vec<_>, _>>()
+        }
+    })?;
+    pieces.iter().map(fold::unfold).collect()
 }
 
 #[tauri::command]
-pub fn export_svg(piece_ids: Vec<String>) -> Result<String, String> {
-    let pieces = get_pieces_by_ids(&piece_ids)?;
+pub fn export_svg(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_ids: Vec<String>,
+) -> AppResult<String> {
+    let pieces = get_pieces_by_ids(&documents, &document_id, &piece_ids)?;
     Ok(export::pieces_to_svg(&pieces))
 }
 
 #[tauri::command]
-pub fn export_svg_to_file(piece_ids: Vec<String>, path: String) -> Result<(), String> {
-    let pieces = get_pieces_by_ids(&piece_ids)?;
+pub fn export_svg_to_file(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_ids: Vec<String>,
+    path: String,
+) -> AppResult<()> {
+    let pieces = get_pieces_by_ids(&documents, &document_id, &piece_ids)?;
     export::save_svg(&pieces, &path)
 }
 
 #[tauri::command]
-pub fn export_dxf(piece_ids: Vec<String>, path: String) -> Result<(), String> {
-    let pieces = get_pieces_by_ids(&piece_ids)?;
+pub fn export_dxf(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_ids: Vec<String>,
+    path: String,
+) -> AppResult<()> {
+    let pieces = get_pieces_by_ids(&documents, &document_id, &piece_ids)?;
     export::save_dxf(&pieces, &path)
 }
 
 #[tauri::command]
-pub fn export_pdf(piece_ids: Vec<String>, path: String, paper_size: String) -> Result<(), String> {
-    let pieces = get_pieces_by_ids(&piece_ids)?;
+pub fn export_pdf(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_ids: Vec<String>,
+    path: String,
+    paper_size: String,
+) -> AppResult<()> {
+    let pieces = get_pieces_by_ids(&documents, &document_id, &piece_ids)?;
     let paper = match paper_size.as_str() {
         "letter" => PaperSize::Letter,
         _ => PaperSize::A4,
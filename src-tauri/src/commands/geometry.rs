@@ -1,23 +1,104 @@
+use tauri::State;
+
 use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::geometry::transform::Transform2D;
+use crate::types::diagnostic::Diagnostic;
+use crate::types::error::AppResult;
+use crate::types::measurement::PieceMeasurements;
 use crate::types::pattern::{PatternPieceData, PatternPieceId};
+use crate::types::repair::RepairReport;
+use crate::types::seam::{OutlineSection, SeamLengthMatch};
 
 #[tauri::command]
 pub fn compute_seam_allowance(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
     piece_id: PatternPieceId,
     allowance_mm: f64,
-) -> Result<PatternPieceData, String> {
-    let piece = engine::pattern_piece::get(&piece_id)?;
-    engine::seam::compute_seam_allowance(&piece, allowance_mm)
+) -> AppResult<PatternPieceData> {
+    let piece = documents.with(&document_id, |project| {
+        engine::pattern_piece::get(project, &piece_id)
+    })?;
+    engine::seam_allowance::compute_seam_allowance(&piece, allowance_mm)
 }
 
 #[tauri::command]
-pub fn validate_piece_geometry(piece_id: PatternPieceId) -> Result<Vec<String>, String> {
-    let piece = engine::pattern_piece::get(&piece_id)?;
-    Ok(engine::validation::validate(&piece))
+pub fn validate_piece_geometry(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_id: PatternPieceId,
+) -> AppResult<Vec<Diagnostic>> {
+    documents.with(&document_id, |project| {
+        let piece = engine::pattern_piece::get(project, &piece_id)?;
+        let mut diagnostics = engine::validation::validate(&piece);
+        diagnostics.extend(engine::seams::check_piece(project, &piece_id));
+        Ok(diagnostics)
+    })
 }
 
 #[tauri::command]
-pub fn compute_piece_area(piece_id: PatternPieceId) -> Result<f64, String> {
-    let piece = engine::pattern_piece::get(&piece_id)?;
+pub fn compute_piece_area(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_id: PatternPieceId,
+) -> AppResult<f64> {
+    let piece = documents.with(&document_id, |project| {
+        engine::pattern_piece::get(project, &piece_id)
+    })?;
     engine::validation::compute_area(&piece)
 }
+
+#[tauri::command]
+pub fn measure_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_id: PatternPieceId,
+) -> AppResult<PieceMeasurements> {
+    let piece = documents.with(&document_id, |project| {
+        engine::pattern_piece::get(project, &piece_id)
+    })?;
+    Ok(engine::measure::measure_piece(&piece))
+}
+
+#[tauri::command]
+pub fn match_seam_lengths(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    a: OutlineSection,
+    b: OutlineSection,
+    notch_count: u32,
+) -> AppResult<SeamLengthMatch> {
+    let (piece_a, piece_b) = documents.with(&document_id, |project| {
+        Ok((
+            engine::pattern_piece::get(project, &a.piece_id)?,
+            engine::pattern_piece::get(project, &b.piece_id)?,
+        ))
+    })?;
+    engine::seam_match::match_sections(&piece_a, &a, &piece_b, &b, notch_count)
+}
+
+#[tauri::command]
+pub fn repair_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_id: PatternPieceId,
+    tolerance_mm: f64,
+) -> AppResult<RepairReport> {
+    documents.with(&document_id, |project| {
+        engine::repair::apply_repair(project, &piece_id, tolerance_mm)
+    })
+}
+
+/// Apply `transform` to a stored piece as one undoable edit, returning the updated piece.
+#[tauri::command]
+pub fn transform_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_id: PatternPieceId,
+    transform: Transform2D,
+) -> AppResult<PatternPieceData> {
+    documents.with(&document_id, |project| {
+        engine::transform::apply_transform(project, &piece_id, &transform)
+    })
+}
@@ -1,24 +1,44 @@
-use crate::engine::{history, pattern_piece};
+use tauri::State;
+
+use crate::engine::document::{DocumentId, Documents};
+use crate::engine::history;
+use crate::types::error::AppResult;
+use crate::types::history::HistoryState;
+
+#[tauri::command]
+pub fn undo(documents: State<'_, Documents>, document_id: DocumentId) -> AppResult<()> {
+    documents.with(&document_id, history::undo)
+}
+
+#[tauri::command]
+pub fn redo(documents: State<'_, Documents>, document_id: DocumentId) -> AppResult<()> {
+    documents.with(&document_id, history::redo)
+}
 
 #[tauri::command]
-pub fn undo() -> Result<(), String> {
-    let previous = history::pop_undo().ok_or("Nothing to undo")?;
-    let current = pattern_piece::snapshot();
-    history::push_redo(current);
-    pattern_piece::restore(previous);
-    Ok(())
+pub fn can_undo_redo(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<(bool, bool)> {
+    documents.with(&document_id, |project| {
+        Ok((history::can_undo(project), history::can_redo(project)))
+    })
 }
 
 #[tauri::command]
-pub fn redo() -> Result<(), String> {
-    let next = history::pop_redo().ok_or("Nothing to redo")?;
-    let current = pattern_piece::snapshot();
-    history::push_undo_only(current);
-    pattern_piece::restore(next);
-    Ok(())
+pub fn get_history(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<HistoryState> {
+    documents.with(&document_id, |project| Ok(history::state(project)))
 }
 
+/// Undo or redo until exactly the first `index` history entries are applied.
 #[tauri::command]
-pub fn can_undo_redo() -> Result<(bool, bool), String> {
-    Ok((history::can_undo(), history::can_redo()))
+pub fn jump_to_history(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    index: usize,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| history::jump_to(project, index))
 }
@@ -1,12 +1,22 @@
+use tauri::State;
+
 use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
 use crate::mesh::generator;
+use crate::types::error::AppResult;
 use crate::types::mesh::MeshData;
 
 #[tauri::command]
-pub fn generate_3d_mesh(piece_ids: Vec<String>) -> Result<MeshData, String> {
-    let mut pieces = Vec::new();
-    for id in &piece_ids {
-        pieces.push(engine::pattern_piece::get(id)?);
-    }
+pub fn generate_3d_mesh(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece_ids: Vec<String>,
+) -> AppResult<MeshData> {
+    let pieces = documents.with(&document_id, |project| {
+        piece_ids
+            .iter()
+            .map(|id| engine::pattern_piece::get(project, id))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
     generator::generate_mesh(&pieces)
 }
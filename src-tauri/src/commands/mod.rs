@@ -1,6 +1,11 @@
+pub mod asset;
+pub mod batch;
+pub mod checkpoint;
+pub mod document;
 pub mod export;
 pub mod geometry;
 pub mod history;
 pub mod mesh;
 pub mod pattern;
 pub mod project;
+pub mod seam;
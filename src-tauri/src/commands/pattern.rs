@@ -1,27 +1,61 @@
+use tauri::State;
+
 use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::types::error::AppResult;
 use crate::types::pattern::{PatternPieceData, PatternPieceId};
 
 #[tauri::command]
-pub fn create_pattern_piece(piece: PatternPieceData) -> Result<PatternPieceId, String> {
-    engine::pattern_piece::create(piece)
+pub fn create_pattern_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    piece: PatternPieceData,
+) -> AppResult<PatternPieceId> {
+    documents.with(&document_id, |project| {
+        engine::pattern_piece::create(project, piece)
+    })
 }
 
 #[tauri::command]
-pub fn update_pattern_piece(id: PatternPieceId, piece: PatternPieceData) -> Result<(), String> {
-    engine::pattern_piece::update(id, piece)
+pub fn update_pattern_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: PatternPieceId,
+    piece: PatternPieceData,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| {
+        engine::pattern_piece::update(project, id, piece)
+    })
 }
 
 #[tauri::command]
-pub fn delete_pattern_piece(id: PatternPieceId) -> Result<(), String> {
-    engine::pattern_piece::delete(&id)
+pub fn delete_pattern_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: PatternPieceId,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| {
+        engine::pattern_piece::delete(project, &id)
+    })
 }
 
 #[tauri::command]
-pub fn get_all_pieces() -> Result<Vec<PatternPieceData>, String> {
-    engine::pattern_piece::get_all()
+pub fn get_all_pieces(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<Vec<PatternPieceData>> {
+    documents.with(&document_id, |project| {
+        Ok(engine::pattern_piece::get_all(project))
+    })
 }
 
 #[tauri::command]
-pub fn get_piece(id: PatternPieceId) -> Result<PatternPieceData, String> {
-    engine::pattern_piece::get(&id)
+pub fn get_piece(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: PatternPieceId,
+) -> AppResult<PatternPieceData> {
+    documents.with(&document_id, |project| {
+        engine::pattern_piece::get(project, &id)
+    })
 }
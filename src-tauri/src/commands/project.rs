@@ -1,62 +1,118 @@
-use tauri::Manager;
+use std::path::PathBuf;
 
+use tauri::{Manager, State};
+
+use crate::engine::document::{DocumentId, Documents};
 use crate::engine::project;
+use crate::types::error::{AppError, AppResult};
 use crate::types::pattern::PatternPieceData;
+use crate::types::project::{ProjectFormat, RecoveryStatus};
 
+/// Save the project, with up to `history_depth` undo steps (none for 0) and keeping
+/// `backup_count` previous versions beside it. Without a `format`, it is bundled only if it
+/// has embedded files.
 #[tauri::command]
-pub fn save_project(path: String) -> Result<(), String> {
-    project::save_project(&path)
+pub fn save_project(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    path: String,
+    history_depth: Option<usize>,
+    format: Option<ProjectFormat>,
+    backup_count: Option<usize>,
+) -> AppResult<()> {
+    documents.with(&document_id, |p| {
+        project::save_project(
+            p,
+            &path,
+            history_depth.unwrap_or(project::DEFAULT_HISTORY_DEPTH),
+            format,
+            backup_count.unwrap_or(project::DEFAULT_BACKUP_COUNT),
+        )
+    })
 }
 
 #[tauri::command]
-pub fn load_project(path: String) -> Result<Vec<PatternPieceData>, String> {
-    project::load_project(&path)
+pub fn load_project(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    path: String,
+) -> AppResult<Vec<PatternPieceData>> {
+    documents.with(&document_id, |p| project::load_project(p, &path))
 }
 
 #[tauri::command]
-pub fn new_project() -> Result<(), String> {
-    project::new_project();
-    Ok(())
+pub fn new_project(documents: State<'_, Documents>, document_id: DocumentId) -> AppResult<()> {
+    documents.with(&document_id, |p| {
+        project::new_project(p);
+        Ok(())
+    })
 }
 
+/// Snapshot the document for recovery after a crash, in its own recovery directory.
 #[tauri::command]
-pub fn save_recovery(app_handle: tauri::AppHandle) -> Result<(), String> {
-    let dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Path error: {e}"))?;
-    project::save_recovery(&dir)
+pub fn save_recovery(
+    app_handle: tauri::AppHandle,
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<()> {
+    let dir = app_data_dir(&app_handle)?;
+    documents.with(&document_id, |p| {
+        project::save_recovery(p, &dir, &document_id)
+    })
 }
 
+/// List the recovery snapshots of documents that aren't open in any window.
 #[tauri::command]
 pub fn check_recovery(
     app_handle: tauri::AppHandle,
-) -> Result<Option<Vec<PatternPieceData>>, String> {
-    let dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Path error: {e}"))?;
-    project::check_recovery(&dir)
+    documents: State<'_, Documents>,
+) -> AppResult<RecoveryStatus> {
+    project::check_recovery(&app_data_dir(&app_handle)?, &documents.ids()?)
 }
 
+/// Delete the recovery snapshots of documents that aren't open in any window.
 #[tauri::command]
-pub fn clear_recovery(app_handle: tauri::AppHandle) -> Result<(), String> {
-    let dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Path error: {e}"))?;
-    project::clear_recovery(&dir)
+pub fn clear_recovery(
+    app_handle: tauri::AppHandle,
+    documents: State<'_, Documents>,
+) -> AppResult<()> {
+    project::clear_recovery(&app_data_dir(&app_handle)?, &documents.ids()?)
 }
 
+/// Delete the unreadable recovery files of documents that aren't open in any window.
 #[tauri::command]
-pub fn restore_recovery(app_handle: tauri::AppHandle) -> Result<Vec<PatternPieceData>, String> {
-    let dir = app_handle
+pub fn delete_unreadable_recovery(
+    app_handle: tauri::AppHandle,
+    documents: State<'_, Documents>,
+) -> AppResult<()> {
+    project::delete_unreadable_recovery(&app_data_dir(&app_handle)?, &documents.ids()?)
+}
+
+/// Open a recovery snapshot into a document, the newest if `id` is omitted, and delete the
+/// snapshots of the document it was taken from.
+#[tauri::command]
+pub fn restore_recovery(
+    app_handle: tauri::AppHandle,
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: Option<String>,
+) -> AppResult<Vec<PatternPieceData>> {
+    let recovered = project::take_recovery(
+        &app_data_dir(&app_handle)?,
+        &documents.ids()?,
+        id.as_deref(),
+    )?;
+    let pieces = recovered.pieces.clone();
+    documents.with(&document_id, |p| {
+        project::open_file(p, recovered);
+        Ok(())
+    })?;
+    Ok(pieces)
+}
+
+fn app_data_dir(app_handle: &tauri::AppHandle) -> AppResult<PathBuf> {
+    app_handle
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Path error: {e}"))?;
-    let pieces = project::check_recovery(&dir)?.ok_or("No recovery data found")?;
-    crate::engine::pattern_piece::replace_all(pieces.clone());
-    crate::engine::history::clear();
-    project::clear_recovery(&dir)?;
-    Ok(pieces)
+        .map_err(|e| AppError::internal(format!("Path error: {e}")))
 }
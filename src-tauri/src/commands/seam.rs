@@ -0,0 +1,66 @@
+use tauri::State;
+
+use crate::engine;
+use crate::engine::document::{DocumentId, Documents};
+use crate::types::diagnostic::Diagnostic;
+use crate::types::error::{AppError, AppResult};
+use crate::types::seam::{Seam, SeamId};
+
+#[tauri::command]
+pub fn create_seam(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    seam: Seam,
+) -> AppResult<SeamId> {
+    documents.with(&document_id, |project| engine::seams::create(project, seam))
+}
+
+#[tauri::command]
+pub fn update_seam(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: SeamId,
+    seam: Seam,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| {
+        engine::seams::update(project, id, seam)
+    })
+}
+
+#[tauri::command]
+pub fn delete_seam(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    id: SeamId,
+) -> AppResult<()> {
+    documents.with(&document_id, |project| engine::seams::delete(project, &id))
+}
+
+#[tauri::command]
+pub fn get_all_seams(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+) -> AppResult<Vec<Seam>> {
+    documents.with(&document_id, |project| Ok(engine::seams::get_all(project)))
+}
+
+#[tauri::command]
+pub fn validate_seams(
+    documents: State<'_, Documents>,
+    document_id: DocumentId,
+    tolerance_mm: f64,
+) -> AppResult<Vec<Diagnostic>> {
+    if !tolerance_mm.is_finite() || tolerance_mm < 0.0 {
+        return Err(AppError::invalid_input(format!(
+            "Invalid seam length tolerance: {tolerance_mm}"
+        )));
+    }
+    documents.with(&document_id, |project| {
+        let seams = engine::seams::get_all(project);
+        Ok(engine::validation::validate_seams(
+            &seams,
+            &project.pieces,
+            tolerance_mm,
+        ))
+    })
+}
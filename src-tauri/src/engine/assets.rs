@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::types::asset::{Asset, AssetId, AssetInfo, AssetKind};
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+
+use super::document::Project;
+use super::storage;
+
+/// Replace all assets (used by project load).
+pub fn replace_all(project: &mut Project, assets: Vec<Asset>) {
+    project.assets = assets;
+}
+
+/// Embed the file at `path` in the project.
+pub fn import(project: &mut Project, kind: AssetKind, path: &Path) -> AppResult<AssetInfo> {
+    let data = fs::read(path).map_err(|e| storage::read_error(e, path))?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::invalid_input("Asset path has no file name").with_path(path))?
+        .to_string();
+    let info = AssetInfo {
+        id: Uuid::new_v4().to_string(),
+        kind,
+        media_type: media_type(&name).to_string(),
+        name,
+        size_bytes: data.len(),
+    };
+    project.assets.push(Asset {
+        info: info.clone(),
+        data,
+    });
+    project.notify(ChangeEvent::AssetsChanged);
+    Ok(info)
+}
+
+pub fn list(project: &Project) -> Vec<AssetInfo> {
+    project.assets.iter().map(|a| a.info.clone()).collect()
+}
+
+pub fn data(project: &Project, id: &AssetId) -> AppResult<Vec<u8>> {
+    project
+        .assets
+        .iter()
+        .find(|a| &a.info.id == id)
+        .map(|a| a.data.clone())
+        .ok_or_else(|| AppError::not_found(format!("Asset not found: {id}")))
+}
+
+pub fn remove(project: &mut Project, id: &AssetId) -> AppResult<()> {
+    let index = project
+        .assets
+        .iter()
+        .position(|a| &a.info.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Asset not found: {id}")))?;
+    project.assets.remove(index);
+    project.notify(ChangeEvent::AssetsChanged);
+    Ok(())
+}
+
+/// Guess a media type from a file name's extension.
+pub fn media_type(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("csv") => "text/csv",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
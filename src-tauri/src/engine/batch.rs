@@ -0,0 +1,143 @@
+use crate::types::batch::BatchOp;
+use crate::types::error::AppResult;
+
+use super::document::Project;
+use super::{history, pattern_piece, seams, transform};
+
+/// Apply `ops` in order as a single undo step. If any operation fails, everything the batch
+/// already changed is rolled back and the error names the failing operation.
+///
+/// The step is labelled `label`, or else after what the operations do.
+///
+/// Returns, for each operation, the id of the piece or seam it created, if any.
+pub fn apply_batch(
+    project: &mut Project,
+    ops: Vec<BatchOp>,
+    label: Option<String>,
+) -> AppResult<Vec<Option<String>>> {
+    if ops.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    history::begin_transaction(project, label.or_else(|| default_label(&ops)))?;
+    let results: AppResult<Vec<_>> = ops
+        .into_iter()
+        .enumerate()
+        .map(|(i, op)| apply_op(project, op).map_err(|e| e.in_operation(i)))
+        .collect();
+
+    match results {
+        Ok(created) => {
+            history::commit_transaction(project)?;
+            Ok(created)
+        }
+        Err(e) => {
+            history::rollback_transaction(project)?;
+            Err(e)
+        }
+    }
+}
+
+/// Describe several operations of one kind, e.g. "Delete 3 pieces". A single operation is
+/// left to label itself.
+fn default_label(ops: &[BatchOp]) -> Option<String> {
+    if ops.len() < 2 {
+        return None;
+    }
+    let kind = describe(&ops[0]);
+    if ops.iter().all(|op| describe(op) == kind) {
+        let (verb, noun) = kind;
+        Some(format!("{verb} {} {noun}s", ops.len()))
+    } else {
+        Some(format!("Edit {} items", ops.len()))
+    }
+}
+
+fn describe(op: &BatchOp) -> (&'static str, &'static str) {
+    match op {
+        BatchOp::CreatePiece { .. } => ("Create", "piece"),
+        BatchOp::UpdatePiece { .. } => ("Edit", "piece"),
+        BatchOp::DeletePiece { .. } => ("Delete", "piece"),
+        BatchOp::TransformPiece { .. } => ("Transform", "piece"),
+        BatchOp::DuplicatePiece { .. } => ("Duplicate", "piece"),
+        BatchOp::CreateSeam { .. } => ("Add", "seam"),
+        BatchOp::UpdateSeam { .. } => ("Edit", "seam"),
+        BatchOp::DeleteSeam { .. } => ("Delete", "seam"),
+    }
+}
+
+fn apply_op(project: &mut Project, op: BatchOp) -> AppResult<Option<String>> {
+    match op {
+        BatchOp::CreatePiece { piece } => pattern_piece::create(project, piece).map(Some),
+        BatchOp::UpdatePiece { id, piece } => {
+            pattern_piece::update(project, id, piece).map(|_| None)
+        }
+        BatchOp::DeletePiece { id } => pattern_piece::delete(project, &id).map(|_| None),
+        BatchOp::TransformPiece { id, transform } => {
+            transform::apply_transform(project, &id, &transform).map(|_| None)
+        }
+        BatchOp::DuplicatePiece {
+            id,
+            name,
+            transform,
+        } => transform::duplicate_piece(project, &id, name, &transform).map(Some),
+        BatchOp::CreateSeam { seam } => seams::create(project, seam).map(Some),
+        BatchOp::UpdateSeam { id, seam } => seams::update(project, id, seam).map(|_| None),
+        BatchOp::DeleteSeam { id } => seams::delete(project, &id).map(|_| None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::transform::Transform2D;
+    use crate::types::error::AppError;
+
+    fn project_with_piece() -> (Project, String) {
+        let mut project = Project::default();
+        let id =
+            pattern_piece::create(&mut project, pattern_piece::create_default("Front")).unwrap();
+        (project, id)
+    }
+
+    #[test]
+    fn failed_batch_rolls_back_everything() {
+        let (mut project, id) = project_with_piece();
+        let ops = vec![
+            BatchOp::TransformPiece {
+                id: id.clone(),
+                transform: Transform2D::translate(5.0, 0.0),
+            },
+            BatchOp::DeletePiece { id: id.clone() },
+            BatchOp::DeletePiece {
+                id: "missing".to_string(),
+            },
+        ];
+        let error = apply_batch(&mut project, ops, None).unwrap_err();
+
+        assert!(matches!(error, AppError::NotFound { .. }));
+        assert_eq!(error.context().operation, Some(2));
+        assert_eq!(project.pieces[&id].origin.x, 0.0);
+        // Only the piece's creation is on the undo stack, and no transaction is left open.
+        assert_eq!(history::state(&project).entries.len(), 1);
+        assert!(history::begin_transaction(&mut project, None).is_ok());
+    }
+
+    #[test]
+    fn duplicates_are_transformed_copies() {
+        let (mut project, id) = project_with_piece();
+        let ops = vec![BatchOp::DuplicatePiece {
+            id: id.clone(),
+            name: "Copy".to_string(),
+            transform: Transform2D::translate(5.0, 0.0),
+        }];
+        let created = apply_batch(&mut project, ops, None).unwrap();
+
+        let copy = &project.pieces[created[0].as_ref().unwrap()];
+        assert_ne!(copy.id, id);
+        assert_eq!(copy.name, "Copy");
+        assert_eq!(copy.origin.x, 5.0);
+        history::undo(&mut project).unwrap();
+        assert_eq!(project.pieces.len(), 1);
+    }
+}
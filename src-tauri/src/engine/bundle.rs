@@ -0,0 +1,180 @@
+use std::io::{Cursor, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::types::asset::{Asset, AssetInfo};
+use crate::types::error::{AppError, AppResult};
+
+/// Layout version of the bundle itself, separate from the project format version.
+const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_PATH: &str = "manifest.json";
+const PROJECT_PATH: &str = "project.json";
+/// Largest entry read from a bundle, so a corrupt or crafted file can't exhaust memory.
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Table of contents stored at the root of every bundle.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    bundle_version: u32,
+    app_version: String,
+    /// Archive path of the project JSON.
+    project: String,
+    assets: Vec<ManifestAsset>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestAsset {
+    #[serde(flatten)]
+    info: AssetInfo,
+    /// Archive path of the asset's contents.
+    path: String,
+}
+
+/// Whether `data` is a zip bundle rather than a plain JSON project.
+pub fn is_bundle(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+}
+
+/// Pack a project's JSON and its assets into a zip bundle.
+pub fn write(project_json: &str, assets: &[Asset]) -> AppResult<Vec<u8>> {
+    let manifest = Manifest {
+        bundle_version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        project: PROJECT_PATH.to_string(),
+        assets: assets
+            .iter()
+            .map(|a| ManifestAsset {
+                info: a.info.clone(),
+                path: format!("assets/{}/{}", a.info.id, a.info.name),
+            })
+            .collect(),
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| AppError::internal(format!("Serialize error: {e}")))?;
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let files = [
+        (MANIFEST_PATH, manifest_json.as_bytes()),
+        (PROJECT_PATH, project_json.as_bytes()),
+    ]
+    .into_iter()
+    .chain(
+        manifest
+            .assets
+            .iter()
+            .zip(assets)
+            .map(|(entry, asset)| (entry.path.as_str(), asset.data.as_slice())),
+    );
+    for (path, contents) in files {
+        zip.start_file(path, options)
+            .map_err(|e| AppError::internal(format!("Bundle error: {e}")))?;
+        zip.write_all(contents)
+            .map_err(|e| AppError::internal(format!("Bundle error: {e}")))?;
+    }
+    let cursor = zip
+        .finish()
+        .map_err(|e| AppError::internal(format!("Bundle error: {e}")))?;
+    Ok(cursor.into_inner())
+}
+
+/// Unpack a zip bundle into its project JSON and assets.
+pub fn read(data: &[u8]) -> AppResult<(String, Vec<Asset>)> {
+    let mut zip = ZipArchive::new(Cursor::new(data))
+        .map_err(|e| AppError::invalid_project(format!("Bundle error: {e}")))?;
+    let manifest: Manifest =
+        serde_json::from_slice(&read_entry(&mut zip, MANIFEST_PATH, MAX_ENTRY_BYTES)?)
+            .map_err(|e| AppError::invalid_project(format!("Bundle manifest error: {e}")))?;
+    if manifest.bundle_version > BUNDLE_VERSION {
+        return Err(AppError::invalid_project(format!(
+            "This bundle was saved by tcad {} in bundle layout {}, but this version of tcad \
+             only reads up to layout {BUNDLE_VERSION}. Update tcad to open it.",
+            manifest.app_version, manifest.bundle_version
+        )));
+    }
+
+    let project = String::from_utf8(read_entry(&mut zip, &manifest.project, MAX_ENTRY_BYTES)?)
+        .map_err(|e| AppError::invalid_project(format!("Bundle error: {e}")))?;
+    let assets = manifest
+        .assets
+        .into_iter()
+        .map(|entry| {
+            let size = entry.info.size_bytes;
+            let data = read_entry(&mut zip, &entry.path, (size as u64).min(MAX_ENTRY_BYTES))?;
+            if data.len() != size {
+                return Err(AppError::invalid_project(format!(
+                    "Bundle entry {} holds {} bytes, but the manifest lists {size}",
+                    entry.path,
+                    data.len()
+                )));
+            }
+            Ok(Asset {
+                data,
+                info: entry.info,
+            })
+        })
+        .collect::<AppResult<_>>()?;
+    Ok((project, assets))
+}
+
+/// Read one entry, failing rather than reading past `max_bytes`.
+fn read_entry(
+    zip: &mut ZipArchive<Cursor<&[u8]>>,
+    path: &str,
+    max_bytes: u64,
+) -> AppResult<Vec<u8>> {
+    let file = zip
+        .by_name(path)
+        .map_err(|e| AppError::invalid_project(format!("Bundle entry {path}: {e}")))?;
+    let mut contents = Vec::new();
+    file.take(max_bytes + 1)
+        .read_to_end(&mut contents)
+        .map_err(|e| AppError::invalid_project(format!("Bundle entry {path}: {e}")))?;
+    if contents.len() as u64 > max_bytes {
+        return Err(AppError::invalid_project(format!(
+            "Bundle entry {path} is larger than {max_bytes} bytes"
+        )));
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::asset::AssetKind;
+
+    fn asset(data: &[u8], size_bytes: usize) -> Asset {
+        Asset {
+            info: AssetInfo {
+                id: "a".to_string(),
+                kind: AssetKind::FabricSwatch,
+                name: "swatch.png".to_string(),
+                media_type: "image/png".to_string(),
+                size_bytes,
+            },
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn bundle_round_trips() {
+        let bundle = write("{}", &[asset(b"swatch", 6)]).unwrap();
+        assert!(is_bundle(&bundle));
+        let (json, assets) = read(&bundle).unwrap();
+        assert_eq!(json, "{}");
+        assert_eq!(assets[0].data, b"swatch");
+    }
+
+    #[test]
+    fn asset_sizes_must_match_the_manifest() {
+        for listed in [3, 10] {
+            let bundle = write("{}", &[asset(b"swatch", listed)]).unwrap();
+            assert!(matches!(
+                read(&bundle),
+                Err(AppError::InvalidProject { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::types::checkpoint::{
+    Checkpoint, CheckpointDiff, CheckpointId, CheckpointInfo, PieceRef,
+};
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+use crate::types::pattern::PatternPieceData;
+
+use super::document::Project;
+use super::history::{self, Delta};
+use super::{pattern_piece, seams};
+
+/// Replace all checkpoints (used by project load).
+pub fn replace_all(project: &mut Project, checkpoints: Vec<Checkpoint>) {
+    project.checkpoints = checkpoints;
+}
+
+/// Save the current pieces and seams as a checkpoint called `name`.
+pub fn create(project: &mut Project, name: String) -> AppResult<CheckpointId> {
+    if name.trim().is_empty() {
+        return Err(AppError::invalid_input("Checkpoint name must not be empty"));
+    }
+    let checkpoint = Checkpoint {
+        id: Uuid::new_v4().to_string(),
+        name,
+        timestamp_ms: history::now_ms(),
+        pieces: pattern_piece::get_all(project),
+        seams: seams::get_all(project),
+    };
+    let id = checkpoint.id.clone();
+    project.checkpoints.push(checkpoint);
+    project.notify(ChangeEvent::CheckpointsChanged);
+    Ok(id)
+}
+
+pub fn list(project: &Project) -> Vec<CheckpointInfo> {
+    project
+        .checkpoints
+        .iter()
+        .map(|c| CheckpointInfo {
+            id: c.id.clone(),
+            name: c.name.clone(),
+            timestamp_ms: c.timestamp_ms,
+            piece_count: c.pieces.len(),
+            seam_count: c.seams.len(),
+        })
+        .collect()
+}
+
+pub fn delete(project: &mut Project, id: &CheckpointId) -> AppResult<()> {
+    let index = project
+        .checkpoints
+        .iter()
+        .position(|c| &c.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Checkpoint not found: {id}")))?;
+    project.checkpoints.remove(index);
+    project.notify(ChangeEvent::CheckpointsChanged);
+    Ok(())
+}
+
+fn get(project: &Project, id: &CheckpointId) -> AppResult<Checkpoint> {
+    project
+        .checkpoints
+        .iter()
+        .find(|c| &c.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::not_found(format!("Checkpoint not found: {id}")))
+}
+
+/// List the pieces and seams that differ between a checkpoint and the current state.
+pub fn compare(project: &Project, id: &CheckpointId) -> AppResult<CheckpointDiff> {
+    let checkpoint = get(project, id)?;
+    let current = &project.pieces;
+    let saved: HashMap<_, _> = checkpoint.pieces.iter().map(|p| (&p.id, p)).collect();
+    let piece_ref = |p: &PatternPieceData| PieceRef {
+        id: p.id.clone(),
+        name: p.name.clone(),
+    };
+
+    let mut diff = CheckpointDiff {
+        added_pieces: Vec::new(),
+        removed_pieces: Vec::new(),
+        changed_pieces: Vec::new(),
+        added_seams: 0,
+        removed_seams: 0,
+        changed_seams: 0,
+    };
+    for piece in current.values() {
+        match saved.get(&piece.id) {
+            None => diff.added_pieces.push(piece_ref(piece)),
+            Some(old) if !same(*old, piece) => diff.changed_pieces.push(piece_ref(piece)),
+            Some(_) => {}
+        }
+    }
+    diff.removed_pieces = checkpoint
+        .pieces
+        .iter()
+        .filter(|p| !current.contains_key(&p.id))
+        .map(piece_ref)
+        .collect();
+    for list in [
+        &mut diff.added_pieces,
+        &mut diff.removed_pieces,
+        &mut diff.changed_pieces,
+    ] {
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    let current_seams = seams::get_all(project);
+    let saved_seams: HashMap<_, _> = checkpoint.seams.iter().map(|s| (&s.id, s)).collect();
+    for seam in &current_seams {
+        match saved_seams.get(&seam.id) {
+            None => diff.added_seams += 1,
+            Some(old) if !same(*old, seam) => diff.changed_seams += 1,
+            Some(_) => {}
+        }
+    }
+    let current_ids: HashSet<_> = current_seams.iter().map(|s| &s.id).collect();
+    diff.removed_seams = checkpoint
+        .seams
+        .iter()
+        .filter(|s| !current_ids.contains(&s.id))
+        .count();
+    Ok(diff)
+}
+
+/// Put every piece and seam back as it was in a checkpoint, as one undo step. Only the
+/// pieces and seams that differ from the checkpoint are recorded and restored.
+pub fn restore(project: &mut Project, id: &CheckpointId) -> AppResult<()> {
+    let checkpoint = get(project, id)?;
+    let pieces = changed_entries(&project.pieces, checkpoint.pieces, |p| &p.id);
+    let saved_seams = changed_entries(&project.seams, checkpoint.seams, |s| &s.id);
+    if pieces.is_empty() && saved_seams.is_empty() {
+        return Ok(());
+    }
+
+    let delta = pieces
+        .iter()
+        .fold(Delta::default(), |delta, (id, _)| delta.piece(project, id));
+    let delta = saved_seams
+        .iter()
+        .fold(delta, |delta, (id, _)| delta.seam(project, id));
+    history::record(project, format!("Restore {}", checkpoint.name), delta);
+
+    for (id, piece) in pieces {
+        pattern_piece::restore_entry(project, &id, piece);
+    }
+    for (id, seam) in saved_seams {
+        seams::restore_entry(project, &id, seam);
+    }
+    Ok(())
+}
+
+/// The entries a checkpoint holds differently from `current`, keyed by id: those saved
+/// with other content or not present now, and `None` for those added since.
+fn changed_entries<T: Serialize>(
+    current: &HashMap<String, T>,
+    saved: Vec<T>,
+    id: impl Fn(&T) -> &String,
+) -> Vec<(String, Option<T>)> {
+    let saved_ids: HashSet<_> = saved.iter().map(|v| id(v).clone()).collect();
+    let added = current
+        .keys()
+        .filter(|k| !saved_ids.contains(*k))
+        .map(|k| (k.clone(), None));
+    saved
+        .into_iter()
+        .filter(|v| current.get(id(v)).is_none_or(|c| !same(c, v)))
+        .map(|v| (id(&v).clone(), Some(v)))
+        .chain(added)
+        .collect()
+}
+
+/// Whether two values serialize identically.
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pattern::{CurveSegment, OutlinePosition, PatternPieceId, Point2D};
+    use crate::types::seam::{OutlineSection, Seam, SeamType};
+
+    fn section(piece_id: &str) -> OutlineSection {
+        OutlineSection {
+            piece_id: piece_id.to_string(),
+            start: OutlinePosition { segment: 0, t: 0.0 },
+            end: OutlinePosition { segment: 0, t: 1.0 },
+            reversed: false,
+        }
+    }
+
+    fn square(name: &str) -> PatternPieceData {
+        let mut piece = pattern_piece::create_default(name);
+        piece.outline = [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)]
+            .into_iter()
+            .map(|(x, y)| CurveSegment::Line {
+                end: Point2D { x, y },
+                edge: Default::default(),
+            })
+            .collect();
+        piece
+    }
+
+    fn rename(project: &mut Project, id: &PatternPieceId, name: &str) {
+        let mut piece = project.pieces[id].clone();
+        piece.name = name.to_string();
+        pattern_piece::update(project, id.clone(), piece).unwrap();
+    }
+
+    /// A project with two pieces seamed together.
+    fn seamed_project() -> (Project, PatternPieceId, PatternPieceId) {
+        let mut project = Project::default();
+        let front = pattern_piece::create(&mut project, square("Front")).unwrap();
+        let back = pattern_piece::create(&mut project, square("Back")).unwrap();
+        let seam = Seam {
+            id: String::new(),
+            a: section(&front),
+            b: section(&back),
+            seam_type: SeamType::Plain,
+            ease_mm: 0.0,
+        };
+        seams::create(&mut project, seam).unwrap();
+        (project, front, back)
+    }
+
+    #[test]
+    fn create_saves_the_current_pieces_and_seams() {
+        let (mut project, _, _) = seamed_project();
+        assert!(create(&mut project, " ".to_string()).is_err());
+        let id = create(&mut project, "Toile".to_string()).unwrap();
+
+        let saved = list(&project);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].id, id);
+        assert_eq!(saved[0].name, "Toile");
+        assert_eq!((saved[0].piece_count, saved[0].seam_count), (2, 1));
+
+        delete(&mut project, &id).unwrap();
+        assert!(list(&project).is_empty());
+        assert!(delete(&mut project, &id).is_err());
+    }
+
+    #[test]
+    fn compare_lists_what_changed_since() {
+        let (mut project, front, back) = seamed_project();
+        let id = create(&mut project, "Toile".to_string()).unwrap();
+        let diff = compare(&project, &id).unwrap();
+        assert!(diff.added_pieces.is_empty());
+        assert!(diff.removed_pieces.is_empty());
+        assert!(diff.changed_pieces.is_empty());
+
+        rename(&mut project, &front, "Front Bodice");
+        pattern_piece::delete(&mut project, &back).unwrap();
+        let sleeve = pattern_piece::create(&mut project, square("Sleeve"));
+        let diff = compare(&project, &id).unwrap();
+        assert_eq!(diff.changed_pieces[0].name, "Front Bodice");
+        assert_eq!(diff.removed_pieces[0].name, "Back");
+        assert_eq!(diff.added_pieces[0].id, sleeve.unwrap());
+        assert_eq!(diff.removed_seams, 1);
+    }
+
+    #[test]
+    fn restore_is_one_undo_step() {
+        let (mut project, front, back) = seamed_project();
+        let id = create(&mut project, "Toile".to_string()).unwrap();
+        rename(&mut project, &front, "Front Bodice");
+        pattern_piece::delete(&mut project, &back).unwrap();
+        let sleeve = pattern_piece::create(&mut project, square("Sleeve")).unwrap();
+
+        restore(&mut project, &id).unwrap();
+        assert_eq!(project.pieces[&front].name, "Front");
+        assert!(project.pieces.contains_key(&back));
+        assert!(!project.pieces.contains_key(&sleeve));
+        assert_eq!(project.seams.len(), 1);
+        let entries = history::state(&project).entries;
+        assert_eq!(entries.last().unwrap().label, "Restore Toile");
+
+        history::undo(&mut project).unwrap();
+        assert_eq!(project.pieces[&front].name, "Front Bodice");
+        assert!(!project.pieces.contains_key(&back));
+        assert!(project.pieces.contains_key(&sleeve));
+        assert!(project.seams.is_empty());
+    }
+
+    #[test]
+    fn restore_records_only_what_differs() {
+        let (mut project, front, back) = seamed_project();
+        let id = create(&mut project, "Toile".to_string()).unwrap();
+        let saved = history::save(&project, usize::MAX).entries.len();
+
+        // Nothing to put back, so no undo step.
+        restore(&mut project, &id).unwrap();
+        assert_eq!(history::save(&project, usize::MAX).entries.len(), saved);
+
+        rename(&mut project, &front, "Front Bodice");
+        restore(&mut project, &id).unwrap();
+        let history = history::save(&project, usize::MAX);
+        let delta = &history.entries.last().unwrap().delta;
+        assert_eq!(delta.pieces.keys().collect::<Vec<_>>(), [&front]);
+        assert!(delta.seams.is_empty());
+        assert!(!delta.pieces.contains_key(&back));
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use uuid::Uuid;
+
+use crate::types::asset::Asset;
+use crate::types::checkpoint::Checkpoint;
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+use crate::types::pattern::{PatternPieceData, PatternPieceId};
+use crate::types::seam::{Seam, SeamId};
+
+use super::history::{self, History};
+use super::{pattern_piece, seams};
+
+pub type DocumentId = String;
+
+/// Everything belonging to one open project: its pieces and seams, named checkpoints,
+/// embedded files and undo history.
+#[derive(Default)]
+pub struct Project {
+    pub pieces: HashMap<PatternPieceId, PatternPieceData>,
+    pub seams: HashMap<SeamId, Seam>,
+    /// Oldest first.
+    pub checkpoints: Vec<Checkpoint>,
+    pub assets: Vec<Asset>,
+    pub history: History,
+    /// Changes made since they were last passed to the [`Documents`] listener.
+    pub changes: Vec<ChangeEvent>,
+}
+
+impl Project {
+    /// Note a change for the frontend. Repeats within one command are dropped.
+    pub fn notify(&mut self, change: ChangeEvent) {
+        if !self.changes.contains(&change) {
+            self.changes.push(change);
+        }
+    }
+}
+
+/// Called with a document's id and the changes one command made to it.
+pub type ChangeListener = Box<dyn Fn(&DocumentId, Vec<ChangeEvent>) + Send + Sync>;
+
+/// Every open project, keyed by document id. Held in Tauri managed state so each window
+/// can edit its own document.
+#[derive(Default)]
+pub struct Documents {
+    projects: Mutex<HashMap<DocumentId, Arc<Mutex<Project>>>>,
+    listener: Option<ChangeListener>,
+}
+
+impl Documents {
+    /// Documents that report every change to `listener`.
+    pub fn with_listener(listener: ChangeListener) -> Self {
+        Self {
+            projects: Mutex::default(),
+            listener: Some(listener),
+        }
+    }
+
+    /// Open a new, empty document.
+    pub fn open(&self) -> AppResult<DocumentId> {
+        let id = Uuid::new_v4().to_string();
+        let mut projects = self
+            .projects
+            .lock()
+            .map_err(|e| AppError::internal(format!("Lock error: {e}")))?;
+        projects.insert(id.clone(), Arc::default());
+        Ok(id)
+    }
+
+    /// The ids of every open document.
+    pub fn ids(&self) -> AppResult<Vec<DocumentId>> {
+        let projects = self
+            .projects
+            .lock()
+            .map_err(|e| AppError::internal(format!("Lock error: {e}")))?;
+        Ok(projects.keys().cloned().collect())
+    }
+
+    pub fn close(&self, id: &DocumentId) -> AppResult<()> {
+        let mut projects = self
+            .projects
+            .lock()
+            .map_err(|e| AppError::internal(format!("Lock error: {e}")))?;
+        projects
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| AppError::not_found(format!("Document not found: {id}")))
+    }
+
+    /// Run `f` on a document's project, then report whatever it changed, even if it
+    /// failed part way. Only that project is locked while `f` runs, so other documents stay
+    /// usable.
+    pub fn with<T>(
+        &self,
+        id: &DocumentId,
+        f: impl FnOnce(&mut Project) -> AppResult<T>,
+    ) -> AppResult<T> {
+        let project = {
+            let projects = self
+                .projects
+                .lock()
+                .map_err(|e| AppError::internal(format!("Lock error: {e}")))?;
+            projects
+                .get(id)
+                .cloned()
+                .ok_or_else(|| AppError::not_found(format!("Document not found: {id}")))?
+        };
+        let (result, changes) = {
+            let mut project = project
+                .lock()
+                .map_err(|e| AppError::internal(format!("Lock error: {e}")))?;
+            let result = f(&mut project);
+            (result, mem::take(&mut project.changes))
+        };
+        if let Some(listener) = &self.listener {
+            if !changes.is_empty() {
+                listener(id, changes);
+            }
+        }
+        result
+    }
+}
+
+/// Copies of the given pieces, plus the seams that join two of them, for pasting into
+/// another document.
+pub fn copy_pieces(
+    project: &Project,
+    piece_ids: &[PatternPieceId],
+) -> AppResult<(Vec<PatternPieceData>, Vec<Seam>)> {
+    let pieces = piece_ids
+        .iter()
+        .map(|id| pattern_piece::get(project, id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids: HashSet<_> = piece_ids.iter().collect();
+    let seams = project
+        .seams
+        .values()
+        .filter(|s| ids.contains(&s.a.piece_id) && ids.contains(&s.b.piece_id))
+        .cloned()
+        .collect();
+    Ok((pieces, seams))
+}
+
+/// Add copied pieces and their seams to a project as one undo step, giving them new ids.
+/// Returns the new piece ids, in the order the pieces were given.
+pub fn paste_pieces(
+    project: &mut Project,
+    pieces: Vec<PatternPieceData>,
+    seams: Vec<Seam>,
+) -> AppResult<Vec<PatternPieceId>> {
+    let label = match pieces.as_slice() {
+        [] => return Ok(Vec::new()),
+        [piece] => format!("Paste {}", piece.name),
+        _ => format!("Paste {} pieces", pieces.len()),
+    };
+
+    history::begin_transaction(project, Some(label))?;
+    let result = paste_into(project, pieces, seams);
+    match result {
+        Ok(ids) => {
+            history::commit_transaction(project)?;
+            Ok(ids)
+        }
+        Err(e) => {
+            history::rollback_transaction(project)?;
+            Err(e)
+        }
+    }
+}
+
+fn paste_into(
+    project: &mut Project,
+    pieces: Vec<PatternPieceData>,
+    copied_seams: Vec<Seam>,
+) -> AppResult<Vec<PatternPieceId>> {
+    let mut new_ids = HashMap::new();
+    let mut created = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        let old_id = piece.id.clone();
+        let id = pattern_piece::create(project, piece)?;
+        new_ids.insert(old_id, id.clone());
+        created.push(id);
+    }
+    for mut seam in copied_seams {
+        for section in [&mut seam.a, &mut seam.b] {
+            section.piece_id = new_ids.get(&section.piece_id).cloned().ok_or_else(|| {
+                AppError::invalid_input(format!(
+                    "Seam refers to a piece not being pasted: {}",
+                    section.piece_id
+                ))
+                .with_piece(&section.piece_id)
+            })?;
+        }
+        seams::create(project, seam)?;
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pattern::{CurveSegment, OutlinePosition, Point2D};
+    use crate::types::seam::{OutlineSection, SeamType};
+
+    fn square(project: &mut Project, name: &str) -> PatternPieceId {
+        let mut piece = pattern_piece::create_default(name);
+        piece.outline = [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)]
+            .into_iter()
+            .map(|(x, y)| CurveSegment::Line {
+                end: Point2D { x, y },
+                edge: Default::default(),
+            })
+            .collect();
+        pattern_piece::create(project, piece).unwrap()
+    }
+
+    fn seam(project: &mut Project, a: &PatternPieceId, b: &PatternPieceId) -> SeamId {
+        let section = |piece_id: &PatternPieceId| OutlineSection {
+            piece_id: piece_id.clone(),
+            start: OutlinePosition { segment: 0, t: 0.0 },
+            end: OutlinePosition { segment: 0, t: 1.0 },
+            reversed: false,
+        };
+        let seam = Seam {
+            id: String::new(),
+            a: section(a),
+            b: section(b),
+            seam_type: SeamType::Plain,
+            ease_mm: 0.0,
+        };
+        seams::create(project, seam).unwrap()
+    }
+
+    #[test]
+    fn pasted_seams_join_the_pasted_pieces() {
+        let mut source = Project::default();
+        let front = square(&mut source, "Front");
+        let back = square(&mut source, "Back");
+        let sleeve = square(&mut source, "Sleeve");
+        let joined = seam(&mut source, &front, &back);
+        seam(&mut source, &front, &sleeve);
+
+        let (pieces, copied) = copy_pieces(&source, &[front.clone(), back.clone()]).unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].id, joined);
+
+        let mut target = Project::default();
+        let ids = paste_pieces(&mut target, pieces, copied).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert!(!ids.contains(&front) && !ids.contains(&back));
+        let pasted: Vec<&Seam> = target.seams.values().collect();
+        assert_eq!(pasted.len(), 1);
+        assert_ne!(pasted[0].id, joined);
+        assert_eq!(
+            (&pasted[0].a.piece_id, &pasted[0].b.piece_id),
+            (&ids[0], &ids[1])
+        );
+
+        // The paste is one undo step.
+        history::undo(&mut target).unwrap();
+        assert!(target.pieces.is_empty() && target.seams.is_empty());
+    }
+
+    #[test]
+    fn paste_with_a_dangling_seam_changes_nothing() {
+        let mut source = Project::default();
+        let front = square(&mut source, "Front");
+        let back = square(&mut source, "Back");
+        seam(&mut source, &front, &back);
+        // Only one of the pieces the seam joins is pasted.
+        let (pieces, _) = copy_pieces(&source, &[front]).unwrap();
+        let dangling: Vec<Seam> = source.seams.values().cloned().collect();
+
+        let mut target = Project::default();
+        assert!(paste_pieces(&mut target, pieces, dangling).is_err());
+        assert!(target.pieces.is_empty());
+        assert!(!history::can_undo(&target));
+    }
+}
@@ -2,10 +2,23 @@ use std::fmt::Write;
 use std::fs;
 
 use crate::geometry::bbox::BBox2D;
+use crate::geometry::curve;
 use crate::geometry::vec2::Vec2;
-use crate::types::pattern::{CurveSegment, PatternPieceData, Point2D};
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{CurveSegment, MarkingKind, PatternPieceData, Point2D};
+
+use super::marking::{self, MarkingShape};
+use super::notch;
+use super::validation::CLOSE_TOLERANCE;
 
 const SVG_MARGIN: f64 = 10.0;
+/// Chords used when a marking circle has to be drawn as a polyline.
+const CIRCLE_STEPS: usize = 32;
+
+// AAMA/ASTM D6673 layers for internal markings.
+const AAMA_MIRROR_LINE_LAYER: &str = "6";
+const AAMA_INTERNAL_LINE_LAYER: &str = "8";
+const AAMA_DRILL_HOLE_LAYER: &str = "13";
 
 // --- SVG Export ---
 
@@ -66,20 +79,46 @@ pub fn pieces_to_svg(pieces: &[PatternPieceData]) -> String {
         }
 
         // Notches
-        for notch in &piece.notches {
-            let nx = piece.origin.x + notch.x;
-            let ny = piece.origin.y + notch.y;
+        for polyline in piece_notch_polylines(piece) {
+            let points: Vec<String> = polyline.iter().map(|(x, y)| format!("{x},{y}")).collect();
             writeln!(
                 svg,
-                r#"    <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="0.5"/>"#,
-                nx,
-                ny - 3.0,
-                nx,
-                ny + 3.0
+                r#"    <polyline points="{}" fill="none" stroke="black" stroke-width="0.5"/>"#,
+                points.join(" ")
             )
             .unwrap();
         }
 
+        // Markings
+        for (kind, shape) in piece_marking_shapes(piece) {
+            let dash = if kind == MarkingKind::FoldLine {
+                r#" stroke-dasharray="4,1,1,1""#
+            } else {
+                ""
+            };
+            match shape {
+                MarkingShape::Point(_) => {}
+                MarkingShape::Circle { center, radius } => {
+                    writeln!(
+                        svg,
+                        r#"    <circle cx="{}" cy="{}" r="{radius}" fill="none" stroke="black" stroke-width="0.3"/>"#,
+                        center.x, center.y
+                    )
+                    .unwrap();
+                }
+                MarkingShape::Polyline(line) => {
+                    let points: Vec<String> =
+                        line.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                    writeln!(
+                        svg,
+                        r#"    <polyline points="{}" fill="none" stroke="black" stroke-width="0.3"{dash}/>"#,
+                        points.join(" ")
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
         // Label
         writeln!(
             svg,
@@ -95,9 +134,10 @@ pub fn pieces_to_svg(pieces: &[PatternPieceData]) -> String {
     svg
 }
 
-pub fn save_svg(pieces: &[PatternPieceData], path: &str) -> Result<(), String> {
+pub fn save_svg(pieces: &[PatternPieceData], path: &str) -> AppResult<()> {
     let svg = pieces_to_svg(pieces);
-    fs::write(path, svg).map_err(|e| format!("Write error: {e}"))
+    fs::write(path, svg)
+        .map_err(|e| AppError::export_failed(format!("Write error: {e}")).with_path(path))
 }
 
 fn outline_to_svg_path(origin: &Point2D, segments: &[CurveSegment]) -> String {
@@ -107,10 +147,10 @@ fn outline_to_svg_path(origin: &Point2D, segments: &[CurveSegment]) -> String {
 
     for seg in segments {
         match seg {
-            CurveSegment::Line { end } => {
+            CurveSegment::Line { end, .. } => {
                 write!(d, " L {},{}", origin.x + end.x, origin.y + end.y).unwrap();
             }
-            CurveSegment::QuadraticBezier { control, end } => {
+            CurveSegment::QuadraticBezier { control, end, .. } => {
                 write!(
                     d,
                     " Q {},{} {},{}",
@@ -125,6 +165,7 @@ fn outline_to_svg_path(origin: &Point2D, segments: &[CurveSegment]) -> String {
                 control1,
                 control2,
                 end,
+                ..
             } => {
                 write!(
                     d,
@@ -143,6 +184,7 @@ fn outline_to_svg_path(origin: &Point2D, segments: &[CurveSegment]) -> String {
                 radius,
                 start_angle,
                 end_angle,
+                ..
             } => {
                 // Approximate arc with line segments
                 arc_to_svg_lines(
@@ -195,14 +237,14 @@ pub fn pieces_to_dxf(pieces: &[PatternPieceData]) -> String {
 
         for seg in &piece.outline {
             match seg {
-                CurveSegment::Line { end } => {
+                CurveSegment::Line { end, .. } => {
                     let ex = ox + end.x;
                     let ey = -(piece.origin.y + end.y);
                     write_dxf_line(&mut dxf, layer, cx, cy, ex, ey);
                     cx = ex;
                     cy = ey;
                 }
-                CurveSegment::QuadraticBezier { control, end } => {
+                CurveSegment::QuadraticBezier { control, end, .. } => {
                     // Sample quadratic bezier to polyline
                     let pts = sample_quadratic(
                         cx,
@@ -224,6 +266,7 @@ pub fn pieces_to_dxf(pieces: &[PatternPieceData]) -> String {
                     control1,
                     control2,
                     end,
+                    ..
                 } => {
                     let pts = sample_cubic(
                         cx,
@@ -248,6 +291,7 @@ pub fn pieces_to_dxf(pieces: &[PatternPieceData]) -> String {
                     radius,
                     start_angle,
                     end_angle,
+                    ..
                 } => {
                     // DXF ARC entity
                     let acx = ox + center.x;
@@ -270,15 +314,59 @@ pub fn pieces_to_dxf(pieces: &[PatternPieceData]) -> String {
         if (cx - ox).abs() > 0.001 || (cy - oy).abs() > 0.001 {
             write_dxf_line(&mut dxf, layer, cx, cy, ox, oy);
         }
+
+        for polyline in piece_notch_polylines(piece) {
+            for pair in polyline.windows(2) {
+                write_dxf_line(
+                    &mut dxf, layer, pair[0].0, -pair[0].1, pair[1].0, -pair[1].1,
+                );
+            }
+        }
+
+        // Markings go on the AAMA layers cutting-room software looks for, rather than the
+        // piece layer: drill and button positions as points, everything else as lines.
+        // Every entity on these layers is cut or drilled, so a drill or button position
+        // goes out as its point alone, without the symbol drawn around it.
+        for (kind, shape) in piece_marking_shapes(piece) {
+            let positioned = matches!(kind, MarkingKind::DrillHole | MarkingKind::Button);
+            match shape {
+                MarkingShape::Point(p) => {
+                    write_dxf_point(&mut dxf, AAMA_DRILL_HOLE_LAYER, p.x, -p.y);
+                }
+                _ if positioned => {}
+                MarkingShape::Circle { center, radius } => {
+                    write_dxf_circle(
+                        &mut dxf,
+                        AAMA_INTERNAL_LINE_LAYER,
+                        center.x,
+                        -center.y,
+                        radius,
+                    );
+                }
+                MarkingShape::Polyline(line) => {
+                    let layer = if kind == MarkingKind::FoldLine {
+                        AAMA_MIRROR_LINE_LAYER
+                    } else {
+                        AAMA_INTERNAL_LINE_LAYER
+                    };
+                    for pair in line.windows(2) {
+                        write_dxf_line(
+                            &mut dxf, layer, pair[0].x, -pair[0].y, pair[1].x, -pair[1].y,
+                        );
+                    }
+                }
+            }
+        }
     }
 
     dxf.push_str("0\nENDSEC\n0\nEOF\n");
     dxf
 }
 
-pub fn save_dxf(pieces: &[PatternPieceData], path: &str) -> Result<(), String> {
+pub fn save_dxf(pieces: &[PatternPieceData], path: &str) -> AppResult<()> {
     let dxf = pieces_to_dxf(pieces);
-    fs::write(path, dxf).map_err(|e| format!("Write error: {e}"))
+    fs::write(path, dxf)
+        .map_err(|e| AppError::export_failed(format!("Write error: {e}")).with_path(path))
 }
 
 fn write_dxf_line(dxf: &mut String, layer: &str, x1: f64, y1: f64, x2: f64, y2: f64) {
@@ -289,6 +377,18 @@ fn write_dxf_line(dxf: &mut String, layer: &str, x1: f64, y1: f64, x2: f64, y2:
     .unwrap();
 }
 
+fn write_dxf_point(dxf: &mut String, layer: &str, x: f64, y: f64) {
+    write!(dxf, "0\nPOINT\n8\n{layer}\n10\n{x}\n20\n{y}\n30\n0.0\n").unwrap();
+}
+
+fn write_dxf_circle(dxf: &mut String, layer: &str, cx: f64, cy: f64, radius: f64) {
+    write!(
+        dxf,
+        "0\nCIRCLE\n8\n{layer}\n10\n{cx}\n20\n{cy}\n30\n0.0\n40\n{radius}\n"
+    )
+    .unwrap();
+}
+
 fn write_dxf_arc(
     dxf: &mut String,
     layer: &str,
@@ -372,14 +472,14 @@ fn outline_to_polyline(origin: &Point2D, segments: &[CurveSegment]) -> Vec<(f64,
 
     for seg in segments {
         match seg {
-            CurveSegment::Line { end } => {
+            CurveSegment::Line { end, .. } => {
                 let ex = origin.x + end.x;
                 let ey = origin.y + end.y;
                 pts.push((ex, ey));
                 cx = ex;
                 cy = ey;
             }
-            CurveSegment::QuadraticBezier { control, end } => {
+            CurveSegment::QuadraticBezier { control, end, .. } => {
                 let qpts = sample_quadratic(
                     cx,
                     cy,
@@ -399,6 +499,7 @@ fn outline_to_polyline(origin: &Point2D, segments: &[CurveSegment]) -> Vec<(f64,
                 control1,
                 control2,
                 end,
+                ..
             } => {
                 let cpts = sample_cubic(
                     cx,
@@ -421,6 +522,7 @@ fn outline_to_polyline(origin: &Point2D, segments: &[CurveSegment]) -> Vec<(f64,
                 radius,
                 start_angle,
                 end_angle,
+                ..
             } => {
                 let steps = ((end_angle - start_angle).abs() / 0.1).ceil().max(4.0) as usize;
                 for i in 1..=steps {
@@ -440,11 +542,7 @@ fn outline_to_polyline(origin: &Point2D, segments: &[CurveSegment]) -> Vec<(f64,
     pts
 }
 
-pub fn pieces_to_pdf(
-    pieces: &[PatternPieceData],
-    path: &str,
-    paper: PaperSize,
-) -> Result<(), String> {
+pub fn pieces_to_pdf(pieces: &[PatternPieceData], path: &str, paper: PaperSize) -> AppResult<()> {
     use printpdf::*;
     use std::io::BufWriter;
 
@@ -462,17 +560,18 @@ pub fn pieces_to_pdf(
 
     if pieces.is_empty() {
         let (doc, _, _) = PdfDocument::new("TCAD Export", mm(page_w), mm(page_h), "Layer 1");
-        let file = fs::File::create(path).map_err(|e| format!("File error: {e}"))?;
+        let file = fs::File::create(path)
+            .map_err(|e| AppError::export_failed(format!("File error: {e}")).with_path(path))?;
         doc.save(&mut BufWriter::new(file))
-            .map_err(|e| format!("PDF save error: {e}"))?;
+            .map_err(|e| AppError::export_failed(format!("PDF save error: {e}")).with_path(path))?;
         return Ok(());
     }
 
-    // Pre-flatten all piece outlines to polylines
+    // Pre-flatten all piece outlines, notches and markings to polylines
     let polylines: Vec<Vec<(f64, f64)>> = pieces
         .iter()
         .filter(|p| !p.outline.is_empty())
-        .map(|p| outline_to_polyline(&p.origin, &p.outline))
+        .flat_map(piece_pdf_polylines)
         .collect();
 
     let bbox = compute_pieces_bbox(pieces).expand_by(5.0);
@@ -486,7 +585,7 @@ pub fn pieces_to_pdf(
         PdfDocument::new("TCAD Export", mm(page_w), mm(page_h), "Layer 1");
     let font = doc
         .add_builtin_font(BuiltinFont::Helvetica)
-        .map_err(|e| format!("Font error: {e}"))?;
+        .map_err(|e| AppError::export_failed(format!("Font error: {e}")))?;
 
     for row in 0..rows {
         for col in 0..cols {
@@ -553,14 +652,102 @@ pub fn pieces_to_pdf(
         }
     }
 
-    let file = fs::File::create(path).map_err(|e| format!("File error: {e}"))?;
+    let file = fs::File::create(path)
+        .map_err(|e| AppError::export_failed(format!("File error: {e}")).with_path(path))?;
     doc.save(&mut BufWriter::new(file))
-        .map_err(|e| format!("PDF save error: {e}"))?;
+        .map_err(|e| AppError::export_failed(format!("PDF save error: {e}")).with_path(path))?;
     Ok(())
 }
 
+/// Everything the PDF draws for a piece, flattened to polylines: the outline, then its
+/// notches, then its markings.
+fn piece_pdf_polylines(piece: &PatternPieceData) -> Vec<Vec<(f64, f64)>> {
+    std::iter::once(outline_to_polyline(&piece.origin, &piece.outline))
+        .chain(piece_notch_polylines(piece))
+        .chain(
+            piece_marking_shapes(piece)
+                .into_iter()
+                .filter_map(|(_, shape)| marking_polyline(shape)),
+        )
+        .collect()
+}
+
 // --- Shared helpers ---
 
+/// Notch shapes of a piece as polylines, in the exporters' frame where outline
+/// coordinates are offsets from the piece origin.
+fn piece_notch_polylines(piece: &PatternPieceData) -> Vec<Vec<(f64, f64)>> {
+    let outline: Vec<CurveSegment> = piece
+        .outline
+        .iter()
+        .map(|seg| offset_segment(seg, &piece.origin))
+        .collect();
+    let curves = curve::resolve_closed(&piece.origin, &outline, CLOSE_TOLERANCE);
+    notch::notch_polylines(&curves, &piece.notches)
+        .into_iter()
+        .map(|line| line.iter().map(|p| (p.x, p.y)).collect())
+        .collect()
+}
+
+/// Marking symbols of a piece, in the exporters' frame where positions are offsets from the
+/// piece origin.
+fn piece_marking_shapes(piece: &PatternPieceData) -> Vec<(MarkingKind, MarkingShape)> {
+    let origin = Vec2::from(&piece.origin);
+    piece
+        .markings
+        .iter()
+        .flat_map(|m| {
+            let at = origin + Vec2::from(&m.position);
+            marking::marking_shapes(m, at)
+                .into_iter()
+                .map(move |shape| (m.kind, shape))
+        })
+        .collect()
+}
+
+/// A marking shape as a polyline, with circles flattened. Points have no extent to draw.
+fn marking_polyline(shape: MarkingShape) -> Option<Vec<(f64, f64)>> {
+    match shape {
+        MarkingShape::Point(_) => None,
+        MarkingShape::Circle { center, radius } => Some(
+            (0..=CIRCLE_STEPS)
+                .map(|i| {
+                    let a = std::f64::consts::TAU * i as f64 / CIRCLE_STEPS as f64;
+                    (center.x + radius * a.cos(), center.y + radius * a.sin())
+                })
+                .collect(),
+        ),
+        MarkingShape::Polyline(line) => Some(line.iter().map(|p| (p.x, p.y)).collect()),
+    }
+}
+
+fn offset_segment(seg: &CurveSegment, origin: &Point2D) -> CurveSegment {
+    let shift = |p: &mut Point2D| {
+        p.x += origin.x;
+        p.y += origin.y;
+    };
+    let mut seg = seg.clone();
+    match &mut seg {
+        CurveSegment::Line { end, .. } => shift(end),
+        CurveSegment::QuadraticBezier { control, end, .. } => {
+            shift(control);
+            shift(end);
+        }
+        CurveSegment::CubicBezier {
+            control1,
+            control2,
+            end,
+            ..
+        } => {
+            shift(control1);
+            shift(control2);
+            shift(end);
+        }
+        CurveSegment::Arc { center, .. } => shift(center),
+    }
+    seg
+}
+
 fn compute_pieces_bbox(pieces: &[PatternPieceData]) -> BBox2D {
     let mut bbox = BBox2D::empty();
     for piece in pieces {
@@ -577,16 +764,25 @@ fn compute_pieces_bbox(pieces: &[PatternPieceData]) -> BBox2D {
             bbox.expand_point(&Vec2::new(piece.origin.x + s.x, piece.origin.y + s.y));
             bbox.expand_point(&Vec2::new(piece.origin.x + e.x, piece.origin.y + e.y));
         }
+        for marking in &piece.markings {
+            let at = Vec2::new(
+                piece.origin.x + marking.position.x,
+                piece.origin.y + marking.position.y,
+            );
+            let reach = Vec2::new(marking.size_mm.abs(), marking.size_mm.abs());
+            bbox.expand_point(&(at - reach));
+            bbox.expand_point(&(at + reach));
+        }
     }
     bbox
 }
 
 fn expand_bbox_for_segment(bbox: &mut BBox2D, origin: &Point2D, seg: &CurveSegment) {
     match seg {
-        CurveSegment::Line { end } => {
+        CurveSegment::Line { end, .. } => {
             bbox.expand_point(&Vec2::new(origin.x + end.x, origin.y + end.y));
         }
-        CurveSegment::QuadraticBezier { control, end } => {
+        CurveSegment::QuadraticBezier { control, end, .. } => {
             bbox.expand_point(&Vec2::new(origin.x + control.x, origin.y + control.y));
             bbox.expand_point(&Vec2::new(origin.x + end.x, origin.y + end.y));
         }
@@ -594,6 +790,7 @@ fn expand_bbox_for_segment(bbox: &mut BBox2D, origin: &Point2D, seg: &CurveSegme
             control1,
             control2,
             end,
+            ..
         } => {
             bbox.expand_point(&Vec2::new(origin.x + control1.x, origin.y + control1.y));
             bbox.expand_point(&Vec2::new(origin.x + control2.x, origin.y + control2.y));
@@ -612,3 +809,140 @@ fn expand_bbox_for_segment(bbox: &mut BBox2D, origin: &Point2D, seg: &CurveSegme
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::types::pattern::{Marking, Notch, NotchKind, OutlinePosition};
+
+    /// A 100 mm square called "Front", implicitly closed along its left side.
+    fn square() -> PatternPieceData {
+        let mut piece = create_default("Front");
+        piece.outline = [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]
+            .into_iter()
+            .map(|(x, y)| CurveSegment::Line {
+                end: Point2D { x, y },
+                edge: Default::default(),
+            })
+            .collect();
+        piece
+    }
+
+    /// Number of DXF entities of type `entity` on `layer`.
+    fn dxf_count(dxf: &str, entity: &str, layer: &str) -> usize {
+        dxf.matches(&format!("0\n{entity}\n8\n{layer}\n")).count()
+    }
+
+    fn notched_square() -> PatternPieceData {
+        let mut piece = square();
+        let kinds = [
+            NotchKind::Slit,
+            NotchKind::T,
+            NotchKind::V,
+            NotchKind::Castle,
+            NotchKind::U,
+        ];
+        piece.notches = kinds
+            .into_iter()
+            .enumerate()
+            .map(|(i, kind)| Notch {
+                position: OutlinePosition {
+                    segment: i % 4,
+                    t: 0.25 + 0.5 * (i / 4) as f64,
+                },
+                kind,
+                depth_mm: 6.0,
+                width_mm: 4.0,
+                angle: 0.0,
+            })
+            .collect();
+        piece
+    }
+
+    #[test]
+    fn svg_draws_each_notch_as_a_polyline() {
+        let svg = pieces_to_svg(&[notched_square()]);
+        let polylines: Vec<&str> = svg.lines().filter(|l| l.contains("<polyline")).collect();
+        assert_eq!(polylines.len(), 5);
+        // The slit on the bottom edge runs 6 mm in from (25, 0).
+        assert!(polylines[0].contains(r#"points="25,0 25,6""#));
+    }
+
+    #[test]
+    fn dxf_draws_notches_on_the_piece_layer() {
+        let plain = pieces_to_dxf(&[square()]);
+        let notched = pieces_to_dxf(&[notched_square()]);
+        // Slit 1 line, T 3, V 2, castle 3, and U 2 sides and 8 chords round its end.
+        let notch_lines = 1 + 3 + 2 + 3 + 10;
+        assert_eq!(
+            dxf_count(&notched, "LINE", "Front"),
+            dxf_count(&plain, "LINE", "Front") + notch_lines
+        );
+    }
+
+    /// The square with one marking of each kind.
+    fn marked_square() -> PatternPieceData {
+        let marking = |kind, x, y, size_mm, angle| Marking {
+            kind,
+            position: Point2D { x, y },
+            size_mm,
+            angle,
+        };
+        let mut piece = square();
+        piece.markings = vec![
+            marking(MarkingKind::DrillHole, 20.0, 20.0, 3.0, 0.0),
+            marking(MarkingKind::Button, 50.0, 50.0, 12.0, 0.0),
+            marking(MarkingKind::Buttonhole, 50.0, 30.0, 15.0, 0.0),
+            marking(MarkingKind::FoldLine, 10.0, 0.0, 100.0, 1.0),
+            marking(MarkingKind::PlaceOnFold, 0.0, 80.0, 60.0, 0.0),
+        ];
+        piece
+    }
+
+    #[test]
+    fn svg_draws_marking_symbols() {
+        let svg = pieces_to_svg(&[marked_square()]);
+        // Drill hole and button circles.
+        assert_eq!(svg.matches("<circle").count(), 2);
+        // Drill hole cross 2, buttonhole 3, fold line 1 and place-on-fold bracket 3.
+        assert_eq!(svg.matches("<polyline").count(), 9);
+        assert_eq!(svg.matches(r#"stroke-dasharray="4,1,1,1""#).count(), 1);
+    }
+
+    #[test]
+    fn dxf_puts_markings_on_aama_layers() {
+        let dxf = pieces_to_dxf(&[marked_square()]);
+        // Drill and button positions are points alone, with no symbol to cut.
+        assert_eq!(dxf_count(&dxf, "POINT", AAMA_DRILL_HOLE_LAYER), 2);
+        assert_eq!(dxf.matches("0\nPOINT\n").count(), 2);
+        assert_eq!(dxf.matches("0\nCIRCLE\n").count(), 0);
+        assert_eq!(dxf_count(&dxf, "LINE", AAMA_MIRROR_LINE_LAYER), 1);
+        // Buttonhole 3 lines, and the bracket's 3 sides and 2 strokes per arrowhead.
+        assert_eq!(dxf_count(&dxf, "LINE", AAMA_INTERNAL_LINE_LAYER), 3 + 3 + 4);
+        // Nothing of the markings lands on the piece layer.
+        assert_eq!(
+            dxf_count(&dxf, "LINE", "Front"),
+            dxf_count(&pieces_to_dxf(&[square()]), "LINE", "Front")
+        );
+    }
+
+    #[test]
+    fn pdf_draws_markings_with_the_outline() {
+        let piece = marked_square();
+        let polylines = piece_pdf_polylines(&piece);
+        // The outline, then the symbols of the svg test above; points draw nothing.
+        assert_eq!(polylines.len(), 1 + 2 + 9);
+        let button = &polylines[4];
+        assert_eq!(button.len(), CIRCLE_STEPS + 1);
+        assert!(button
+            .iter()
+            .all(|&(x, y)| ((x - 50.0).hypot(y - 50.0) - 6.0).abs() < 1e-9));
+
+        let path = std::env::temp_dir().join(format!("tcad-export-{}.pdf", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        pieces_to_pdf(&[piece], path, PaperSize::A4).unwrap();
+        assert!(fs::read(path).unwrap().starts_with(b"%PDF"));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{
+    CutOut, EdgeAttributes, Marking, MarkingKind, Notch, OutlinePosition, PatternPieceData,
+};
+
+use super::repair::mirror_corner;
+use super::validation::{CLOSE_TOLERANCE, DEGENERATE_LENGTH};
+
+/// End points of the fold edge of a cut-on-fold piece, or `None` for a piece cut flat.
+/// The fold edge must be a straight segment, or the implicit closing edge, of non-zero length.
+pub fn fold_line(piece: &PatternPieceData) -> AppResult<Option<(Vec2, Vec2)>> {
+    let Some(edge) = piece.fold_edge else {
+        return Ok(None);
+    };
+    let curves = curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE);
+    fold_ends(&curves, edge).map(Some)
+}
+
+fn fold_ends(curves: &[Curve], edge: usize) -> AppResult<(Vec2, Vec2)> {
+    match curves.get(edge) {
+        Some(Curve::Line { from, to }) if from.distance(to) >= DEGENERATE_LENGTH => {
+            Ok((*from, *to))
+        }
+        Some(Curve::Line { .. }) => Err(AppError::invalid_geometry(format!(
+            "Fold edge {edge} has zero length"
+        ))
+        .with_segment(edge)),
+        Some(_) => Err(AppError::invalid_geometry(format!(
+            "Fold edge {edge} is not a straight segment"
+        ))
+        .with_segment(edge)),
+        None => Err(
+            AppError::invalid_geometry(format!("Fold edge {edge} does not exist"))
+                .with_segment(edge),
+        ),
+    }
+}
+
+/// The full shape of a cut-on-fold piece: the half outline followed by its mirror image
+/// across the fold edge, which is replaced by a fold line marking. Notches, cut-outs and
+/// markings are mirrored along with the outline; internal lines and the grain line are kept
+/// as drawn. The origin stays put, and a piece without a fold edge is returned unchanged.
+pub fn unfold(piece: &PatternPieceData) -> AppResult<PatternPieceData> {
+    let Some(k) = piece.fold_edge else {
+        return Ok(piece.clone());
+    };
+    let curves = curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE);
+    let (a, b) = fold_ends(&curves, k)?;
+    let n = curves.len();
+    let edges: Vec<EdgeAttributes> = (0..n)
+        .map(|i| {
+            piece
+                .outline
+                .get(i)
+                .map(|s| s.edge().clone())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    // The rest of the outline runs from b round to a, wrapping past the origin. Its mirror
+    // image runs back from a to b, so each mirrored segment ends at the vertex that used to
+    // end the segment before it.
+    let half: Vec<usize> = (k + 1..n).chain(0..k).collect();
+    let mirrored = half.iter().rev().map(|&i| {
+        let prev = (i + n - 1) % n;
+        let corner = if prev == k {
+            None
+        } else {
+            edges[prev].corner.map(mirror_corner)
+        };
+        let edge = EdgeAttributes {
+            seam_allowance_mm: edges[i].seam_allowance_mm,
+            corner,
+        };
+        curves[i].reflected(a, b).reversed().to_segment(edge)
+    });
+    let m = half.len();
+    // Where a segment other than the fold edge ends up in the unfolded outline.
+    let kept_index = |i: usize| if i < k { i } else { i - 1 + m };
+
+    let mut unfolded = piece.clone();
+    unfolded.fold_edge = None;
+    unfolded.outline = piece.outline[..k.min(piece.outline.len())].to_vec();
+    unfolded.outline.extend(mirrored);
+    unfolded
+        .outline
+        .extend(piece.outline.iter().skip(k + 1).cloned());
+    // The segment ending at a now meets its own mirror image rather than the fold.
+    if let Some(seg) = unfolded.outline.get_mut(kept_index((k + n - 1) % n)) {
+        seg.edge_mut().corner = None;
+    }
+
+    unfolded.notches = piece
+        .notches
+        .iter()
+        .filter(|notch| notch.position.segment != k && notch.position.segment < n)
+        .flat_map(|notch| {
+            let i = notch.position.segment;
+            let t = notch.position.t;
+            let along_half = if i > k { i - k - 1 } else { n - k - 1 + i };
+            let kept = Notch {
+                position: OutlinePosition {
+                    segment: kept_index(i),
+                    t,
+                },
+                ..notch.clone()
+            };
+            let mirror = Notch {
+                position: OutlinePosition {
+                    segment: k + m - 1 - along_half,
+                    t: 1.0 - t,
+                },
+                angle: -notch.angle,
+                ..notch.clone()
+            };
+            [kept, mirror]
+        })
+        .collect();
+
+    unfolded
+        .cut_outs
+        .extend(piece.cut_outs.iter().map(|c| reflect_cut_out(c, a, b)));
+    unfolded
+        .markings
+        .extend(piece.markings.iter().map(|mk| reflect_marking(mk, a, b)));
+    unfolded.markings.push(Marking {
+        kind: MarkingKind::FoldLine,
+        position: a.into(),
+        size_mm: a.distance(&b),
+        angle: (b - a).angle(),
+    });
+
+    Ok(unfolded)
+}
+
+fn reflect_cut_out(cut_out: &CutOut, a: Vec2, b: Vec2) -> CutOut {
+    CutOut {
+        origin: Vec2::from(&cut_out.origin).reflected(&a, &b).into(),
+        outline: curve::resolve(&cut_out.origin, &cut_out.outline)
+            .iter()
+            .zip(&cut_out.outline)
+            .map(|(c, seg)| c.reflected(a, b).to_segment(seg.edge().clone()))
+            .collect(),
+    }
+}
+
+/// Mirroring flips handedness, so directed markings run back from their mirrored far end.
+/// That keeps a "place on fold" bracket on the mirrored side of its fold.
+fn reflect_marking(marking: &Marking, a: Vec2, b: Vec2) -> Marking {
+    let position = Vec2::from(&marking.position);
+    let twice = 2.0 * (b - a).angle();
+    match marking.kind {
+        MarkingKind::DrillHole | MarkingKind::Button => Marking {
+            position: position.reflected(&a, &b).into(),
+            angle: twice - marking.angle,
+            ..marking.clone()
+        },
+        _ => {
+            let far =
+                position + Vec2::new(marking.angle.cos(), marking.angle.sin()) * marking.size_mm;
+            Marking {
+                position: far.reflected(&a, &b).into(),
+                angle: twice - marking.angle + std::f64::consts::PI,
+                ..marking.clone()
+            }
+        }
+    }
+}
@@ -1,73 +1,431 @@
-use std::collections::HashMap;
-use std::sync::{LazyLock, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
+
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+use crate::types::history::{HistoryEntry, HistoryState};
 use crate::types::pattern::{PatternPieceData, PatternPieceId};
+use crate::types::seam::{Seam, SeamId};
+
+use super::document::Project;
+use super::{pattern_piece, seams};
+
+/// Approximate memory (bytes) the undo and redo stacks may hold together. The oldest undo
+/// steps are dropped beyond it, though the most recent step is always kept.
+const HISTORY_BUDGET_BYTES: usize = 32 * 1024 * 1024;
+
+/// The state of the pieces and seams one undo step touched, from before the step.
+/// `None` means the piece or seam did not exist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Delta {
+    pub pieces: HashMap<PatternPieceId, Option<PatternPieceData>>,
+    pub seams: HashMap<SeamId, Option<Seam>>,
+}
+
+impl Delta {
+    /// Record the current state of a piece.
+    pub fn piece(mut self, project: &Project, id: &PatternPieceId) -> Self {
+        self.pieces
+            .insert(id.clone(), project.pieces.get(id).cloned());
+        self
+    }
+
+    /// Record the current state of a seam.
+    pub fn seam(mut self, project: &Project, id: &SeamId) -> Self {
+        self.seams
+            .insert(id.clone(), project.seams.get(id).cloned());
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pieces.is_empty() && self.seams.is_empty()
+    }
 
-const MAX_UNDO: usize = 100;
+    /// Fold a later delta into this one. Entries already present are older, so they win.
+    fn merge(&mut self, later: Delta) {
+        for (id, piece) in later.pieces {
+            self.pieces.entry(id).or_insert(piece);
+        }
+        for (id, seam) in later.seams {
+            self.seams.entry(id).or_insert(seam);
+        }
+    }
 
-type Snapshot = HashMap<PatternPieceId, PatternPieceData>;
+    /// Put everything this delta touched back to its recorded state, returning the delta
+    /// that reverses that.
+    fn apply(self, project: &mut Project) -> Delta {
+        let mut inverse = Delta::default();
+        for (id, piece) in self.pieces {
+            inverse = inverse.piece(project, &id);
+            pattern_piece::restore_entry(project, &id, piece);
+        }
+        for (id, seam) in self.seams {
+            inverse = inverse.seam(project, &id);
+            seams::restore_entry(project, &id, seam);
+        }
+        inverse
+    }
+}
 
-static HISTORY: LazyLock<Mutex<History>> = LazyLock::new(|| Mutex::new(History::new()));
+/// Undo and redo steps as stored in a project file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedHistory {
+    /// Oldest first, laid out as in [`state`].
+    pub entries: Vec<SavedEntry>,
+    /// How many of `entries` are applied to the saved pieces and seams.
+    pub position: usize,
+}
 
-struct History {
-    undo_stack: Vec<Snapshot>,
-    redo_stack: Vec<Snapshot>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedEntry {
+    #[serde(flatten)]
+    pub info: HistoryEntry,
+    pub delta: Delta,
+}
+
+struct Entry {
+    info: HistoryEntry,
+    delta: Delta,
+    /// Serialized size of `delta`, as an estimate of the memory it holds.
+    size: usize,
+}
+
+impl Entry {
+    fn new(info: HistoryEntry, delta: Delta) -> Self {
+        let size = serde_json::to_vec(&delta).map_or(0, |bytes| bytes.len());
+        Self { info, delta, size }
+    }
+}
+
+/// A group of mutations being collected into one undo step.
+struct Transaction {
+    delta: Delta,
+    /// The step's label; the first mutation's label if none was given.
+    label: Option<String>,
+}
+
+/// The current time in milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// A project's undo and redo steps.
+#[derive(Default)]
+pub struct History {
+    undo_stack: VecDeque<Entry>,
+    redo_stack: Vec<Entry>,
+    /// Changes made since the open transaction began, if there is one.
+    transaction: Option<Transaction>,
+    size: usize,
 }
 
 impl History {
-    fn new() -> Self {
-        Self {
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
+    fn push_undo(&mut self, entry: Entry) {
+        self.size += entry.size;
+        self.undo_stack.push_back(entry);
+        while self.size > HISTORY_BUDGET_BYTES && self.undo_stack.len() > 1 {
+            if let Some(oldest) = self.undo_stack.pop_front() {
+                self.size -= oldest.size;
+            }
         }
     }
+
+    fn push_redo(&mut self, entry: Entry) {
+        self.size += entry.size;
+        self.redo_stack.push(entry);
+    }
+
+    fn pop_undo(&mut self) -> Option<Entry> {
+        let entry = self.undo_stack.pop_back()?;
+        self.size -= entry.size;
+        Some(entry)
+    }
+
+    fn pop_redo(&mut self) -> Option<Entry> {
+        let entry = self.redo_stack.pop()?;
+        self.size -= entry.size;
+        Some(entry)
+    }
+
+    fn clear_redo(&mut self) {
+        self.size -= self.redo_stack.iter().map(|e| e.size).sum::<usize>();
+        self.redo_stack.clear();
+    }
 }
 
-pub fn push_snapshot(snapshot: Snapshot) {
-    let mut h = HISTORY.lock().unwrap();
-    if h.undo_stack.len() >= MAX_UNDO {
-        h.undo_stack.remove(0);
+/// Save the prior state of what a mutation is about to change, as a new undo step labelled
+/// `label`. Inside a transaction the changes are folded into the transaction's single step
+/// instead.
+pub fn record(project: &mut Project, label: impl Into<String>, delta: Delta) {
+    let h = &mut project.history;
+    if let Some(transaction) = &mut h.transaction {
+        transaction.label.get_or_insert_with(|| label.into());
+        transaction.delta.merge(delta);
+        return;
     }
-    h.undo_stack.push(snapshot);
-    h.redo_stack.clear();
+    let info = HistoryEntry {
+        label: label.into(),
+        timestamp_ms: now_ms(),
+    };
+    h.push_undo(Entry::new(info, delta));
+    h.clear_redo();
+    project.notify(ChangeEvent::HistoryChanged);
 }
 
-/// Push to undo stack without clearing redo (used during redo operations).
-pub fn push_undo_only(snapshot: Snapshot) {
-    let mut h = HISTORY.lock().unwrap();
-    if h.undo_stack.len() >= MAX_UNDO {
-        h.undo_stack.remove(0);
+/// Start grouping mutations into a single undo step, labelled `label` or else after the
+/// first mutation.
+pub fn begin_transaction(project: &mut Project, label: Option<String>) -> AppResult<()> {
+    let h = &mut project.history;
+    if h.transaction.is_some() {
+        return Err(AppError::invalid_input(
+            "A transaction is already in progress",
+        ));
     }
-    h.undo_stack.push(snapshot);
+    h.transaction = Some(Transaction {
+        delta: Delta::default(),
+        label,
+    });
+    Ok(())
 }
 
-pub fn pop_undo() -> Option<Snapshot> {
-    let mut h = HISTORY.lock().unwrap();
-    h.undo_stack.pop()
+/// Close the open transaction, recording one undo step for everything it changed.
+pub fn commit_transaction(project: &mut Project) -> AppResult<()> {
+    let h = &mut project.history;
+    let transaction = h.transaction.take().ok_or_else(no_transaction)?;
+    if !transaction.delta.is_empty() {
+        let info = HistoryEntry {
+            label: transaction.label.unwrap_or_default(),
+            timestamp_ms: now_ms(),
+        };
+        h.push_undo(Entry::new(info, transaction.delta));
+        h.clear_redo();
+        project.notify(ChangeEvent::HistoryChanged);
+    }
+    Ok(())
 }
 
-pub fn push_redo(snapshot: Snapshot) {
-    let mut h = HISTORY.lock().unwrap();
-    h.redo_stack.push(snapshot);
+/// Abandon the open transaction, putting pieces and seams back as they were when it began.
+pub fn rollback_transaction(project: &mut Project) -> AppResult<()> {
+    let transaction = project
+        .history
+        .transaction
+        .take()
+        .ok_or_else(no_transaction)?;
+    transaction.delta.apply(project);
+    Ok(())
 }
 
-pub fn pop_redo() -> Option<Snapshot> {
-    let mut h = HISTORY.lock().unwrap();
-    h.redo_stack.pop()
+fn no_transaction() -> AppError {
+    AppError::invalid_input("No transaction in progress")
 }
 
-pub fn clear() {
-    let mut h = HISTORY.lock().unwrap();
-    h.undo_stack.clear();
-    h.redo_stack.clear();
+/// Refuse to move through history while a transaction is open: its delta only covers what
+/// was changed inside it, so rolling back or committing after an undo would be wrong.
+fn ensure_no_transaction(project: &Project) -> AppResult<()> {
+    if project.history.transaction.is_some() {
+        return Err(AppError::invalid_input(
+            "Cannot move through history during a transaction",
+        ));
+    }
+    Ok(())
+}
+
+/// Revert the most recent undo step, making it available to redo.
+pub fn undo(project: &mut Project) -> AppResult<()> {
+    ensure_no_transaction(project)?;
+    let entry = project
+        .history
+        .pop_undo()
+        .ok_or_else(|| AppError::invalid_input("Nothing to undo"))?;
+    let inverse = entry.delta.apply(project);
+    project.history.push_redo(Entry::new(entry.info, inverse));
+    project.notify(ChangeEvent::HistoryChanged);
+    Ok(())
+}
+
+/// Reapply the most recently undone step.
+pub fn redo(project: &mut Project) -> AppResult<()> {
+    ensure_no_transaction(project)?;
+    let entry = project
+        .history
+        .pop_redo()
+        .ok_or_else(|| AppError::invalid_input("Nothing to redo"))?;
+    let inverse = entry.delta.apply(project);
+    project.history.push_undo(Entry::new(entry.info, inverse));
+    project.notify(ChangeEvent::HistoryChanged);
+    Ok(())
+}
+
+/// Undo or redo as many steps as needed so that exactly the first `position` entries of
+/// [`state`] are applied.
+pub fn jump_to(project: &mut Project, position: usize) -> AppResult<()> {
+    ensure_no_transaction(project)?;
+    let h = &project.history;
+    let applied = h.undo_stack.len();
+    let total = applied + h.redo_stack.len();
+    if position > total {
+        return Err(AppError::invalid_input(format!(
+            "History position {position} is out of range (0..={total})"
+        )));
+    }
+    for _ in position..applied {
+        undo(project)?;
+    }
+    for _ in applied..position {
+        redo(project)?;
+    }
+    Ok(())
+}
+
+/// List every undo and redo step, oldest first.
+pub fn state(project: &Project) -> HistoryState {
+    let h = &project.history;
+    let entries = h
+        .undo_stack
+        .iter()
+        .chain(h.redo_stack.iter().rev())
+        .map(|entry| entry.info.clone())
+        .collect();
+    HistoryState {
+        entries,
+        position: h.undo_stack.len(),
+    }
+}
+
+/// Copy the most recent `depth` undo steps and the next `depth` redo steps, for saving.
+pub fn save(project: &Project, depth: usize) -> SavedHistory {
+    let h = &project.history;
+    let undo = h
+        .undo_stack
+        .iter()
+        .skip(h.undo_stack.len().saturating_sub(depth));
+    let redo = h.redo_stack.iter().rev().take(depth);
+    let position = undo.len();
+    let entries = undo
+        .chain(redo)
+        .map(|entry| SavedEntry {
+            info: entry.info.clone(),
+            delta: entry.delta.clone(),
+        })
+        .collect();
+    SavedHistory { entries, position }
+}
+
+/// Replace the history with saved steps (used by project load). The oldest steps are
+/// dropped if they exceed the memory budget.
+pub fn load(project: &mut Project, saved: SavedHistory) {
+    let mut h = History::default();
+    let position = saved.position.min(saved.entries.len());
+    let mut entries = saved.entries;
+    for saved in entries.drain(position..).rev() {
+        h.push_redo(Entry::new(saved.info, saved.delta));
+    }
+    for saved in entries {
+        h.push_undo(Entry::new(saved.info, saved.delta));
+    }
+    project.history = h;
+    project.notify(ChangeEvent::HistoryChanged);
 }
 
-pub fn can_undo() -> bool {
-    let h = HISTORY.lock().unwrap();
-    !h.undo_stack.is_empty()
+pub fn can_undo(project: &Project) -> bool {
+    !project.history.undo_stack.is_empty()
 }
 
-pub fn can_redo() -> bool {
-    let h = HISTORY.lock().unwrap();
-    !h.redo_stack.is_empty()
+pub fn can_redo(project: &Project) -> bool {
+    !project.history.redo_stack.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> PatternPieceData {
+        pattern_piece::create_default(name)
+    }
+
+    #[test]
+    fn undo_and_redo_step_through_edits() {
+        let mut project = Project::default();
+        let id = pattern_piece::create(&mut project, named("Front")).unwrap();
+        let mut renamed = project.pieces[&id].clone();
+        renamed.name = "Back".to_string();
+        pattern_piece::update(&mut project, id.clone(), renamed).unwrap();
+
+        let labels: Vec<_> = state(&project)
+            .entries
+            .into_iter()
+            .map(|e| e.label)
+            .collect();
+        assert_eq!(labels, ["Create Front", "Edit Back"]);
+
+        undo(&mut project).unwrap();
+        assert_eq!(project.pieces[&id].name, "Front");
+        undo(&mut project).unwrap();
+        assert!(project.pieces.is_empty());
+        assert!(undo(&mut project).is_err());
+
+        redo(&mut project).unwrap();
+        redo(&mut project).unwrap();
+        assert_eq!(project.pieces[&id].name, "Back");
+        assert!(!can_redo(&project));
+
+        // A new edit after undoing drops the steps that were undone.
+        undo(&mut project).unwrap();
+        pattern_piece::delete(&mut project, &id).unwrap();
+        assert!(!can_redo(&project));
+        assert_eq!(state(&project).position, 2);
+    }
+
+    #[test]
+    fn transaction_is_one_step() {
+        let mut project = Project::default();
+        begin_transaction(&mut project, Some("Add both".to_string())).unwrap();
+        assert!(begin_transaction(&mut project, None).is_err());
+        pattern_piece::create(&mut project, named("Front")).unwrap();
+        pattern_piece::create(&mut project, named("Back")).unwrap();
+        commit_transaction(&mut project).unwrap();
+
+        assert_eq!(state(&project).entries.len(), 1);
+        assert_eq!(state(&project).entries[0].label, "Add both");
+        undo(&mut project).unwrap();
+        assert!(project.pieces.is_empty());
+    }
+
+    #[test]
+    fn rollback_restores_the_start_of_the_transaction() {
+        let mut project = Project::default();
+        let id = pattern_piece::create(&mut project, named("Front")).unwrap();
+        begin_transaction(&mut project, None).unwrap();
+        pattern_piece::delete(&mut project, &id).unwrap();
+        pattern_piece::create(&mut project, named("Back")).unwrap();
+        rollback_transaction(&mut project).unwrap();
+
+        assert_eq!(project.pieces.len(), 1);
+        assert_eq!(project.pieces[&id].name, "Front");
+        assert_eq!(state(&project).entries.len(), 1);
+        assert!(commit_transaction(&mut project).is_err());
+    }
+    #[test]
+    fn undo_and_redo_wait_for_the_transaction() {
+        let mut project = Project::default();
+        let id = pattern_piece::create(&mut project, named("Front")).unwrap();
+        undo(&mut project).unwrap();
+        redo(&mut project).unwrap();
+
+        begin_transaction(&mut project, None).unwrap();
+        pattern_piece::create(&mut project, named("Back")).unwrap();
+        assert!(undo(&mut project).is_err());
+        assert!(redo(&mut project).is_err());
+        assert!(jump_to(&mut project, 0).is_err());
+        rollback_transaction(&mut project).unwrap();
+
+        assert_eq!(project.pieces.len(), 1);
+        assert!(project.pieces.contains_key(&id));
+        undo(&mut project).unwrap();
+        assert!(project.pieces.is_empty());
+    }
 }
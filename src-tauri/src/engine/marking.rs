@@ -0,0 +1,73 @@
+use crate::geometry::vec2::Vec2;
+use crate::types::pattern::{Marking, MarkingKind};
+
+/// Width of the bar tacks across each end of a buttonhole.
+const BUTTONHOLE_BAR_MM: f64 = 3.0;
+/// Distance from the fold to the bar of a "place on fold" bracket.
+const BRACKET_DEPTH_MM: f64 = 15.0;
+/// Length of the arrowhead strokes on a "place on fold" bracket.
+const BRACKET_ARROW_MM: f64 = 3.0;
+
+/// One drawing primitive of a marking symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkingShape {
+    /// A point a drill or button is positioned at.
+    Point(Vec2),
+    Circle {
+        center: Vec2,
+        radius: f64,
+    },
+    Polyline(Vec<Vec2>),
+}
+
+/// The symbol drawn for `marking` with its position at `at`.
+pub fn marking_shapes(marking: &Marking, at: Vec2) -> Vec<MarkingShape> {
+    let size = marking.size_mm.max(0.0);
+    let dir = Vec2::new(marking.angle.cos(), marking.angle.sin());
+    let across = dir.perpendicular();
+    let end = at + dir * size;
+
+    match marking.kind {
+        MarkingKind::DrillHole => {
+            let (dx, dy) = (Vec2::new(size * 0.5, 0.0), Vec2::new(0.0, size * 0.5));
+            vec![
+                MarkingShape::Point(at),
+                MarkingShape::Circle {
+                    center: at,
+                    radius: size * 0.5,
+                },
+                MarkingShape::Polyline(vec![at - dx, at + dx]),
+                MarkingShape::Polyline(vec![at - dy, at + dy]),
+            ]
+        }
+        MarkingKind::Button => vec![
+            MarkingShape::Point(at),
+            MarkingShape::Circle {
+                center: at,
+                radius: size * 0.5,
+            },
+        ],
+        MarkingKind::Buttonhole => {
+            let bar = across * (BUTTONHOLE_BAR_MM * 0.5);
+            vec![
+                MarkingShape::Polyline(vec![at, end]),
+                MarkingShape::Polyline(vec![at - bar, at + bar]),
+                MarkingShape::Polyline(vec![end - bar, end + bar]),
+            ]
+        }
+        MarkingKind::FoldLine => vec![MarkingShape::Polyline(vec![at, end])],
+        MarkingKind::PlaceOnFold => {
+            let leg = across * BRACKET_DEPTH_MM;
+            let arrow = |tip: Vec2| {
+                let back = across * BRACKET_ARROW_MM;
+                let side = dir * (BRACKET_ARROW_MM * 0.5);
+                MarkingShape::Polyline(vec![tip + back - side, tip, tip + back + side])
+            };
+            vec![
+                MarkingShape::Polyline(vec![at, at + leg, end + leg, end]),
+                arrow(at),
+                arrow(end),
+            ]
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::vec2::Vec2;
+use crate::types::measurement::{PathLength, PieceMeasurements};
+use crate::types::pattern::{CurveSegment, PatternPieceData, Point2D};
+
+/// Measure the outline perimeter, internal lines and cut-outs of a piece.
+/// Internal lines are resolved from the piece origin, like the outline.
+pub fn measure_piece(piece: &PatternPieceData) -> PieceMeasurements {
+    PieceMeasurements {
+        perimeter: path_length(&piece.origin, &piece.outline, true),
+        internal_lines: piece
+            .internal_lines
+            .iter()
+            .map(|line| path_length(&piece.origin, line, false))
+            .collect(),
+        cut_outs: piece
+            .cut_outs
+            .iter()
+            .map(|c| path_length(&c.origin, &c.outline, true))
+            .collect(),
+    }
+}
+
+/// Lengths of a path starting at `origin`. A `closed` path also counts the straight edge
+/// from its last segment back to `origin`.
+pub fn path_length(origin: &Point2D, segments: &[CurveSegment], closed: bool) -> PathLength {
+    let curves = curve::resolve(origin, segments);
+    let lengths: Vec<f64> = curves.iter().map(Curve::length).collect();
+    let closing = match curves.last() {
+        Some(last) if closed => last.end().distance(&Vec2::from(origin)),
+        _ => 0.0,
+    };
+    PathLength {
+        total: lengths.iter().sum::<f64>() + closing,
+        segments: lengths,
+        closing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::types::pattern::CutOut;
+
+    fn p(x: f64, y: f64) -> Point2D {
+        Point2D { x, y }
+    }
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: p(x, y),
+            edge: Default::default(),
+        }
+    }
+
+    #[test]
+    fn line_is_its_chord() {
+        let length = path_length(&p(0.0, 0.0), &[line(30.0, 40.0)], false);
+        assert_eq!(length.segments, [50.0]);
+        assert_eq!((length.total, length.closing), (50.0, 0.0));
+    }
+
+    #[test]
+    fn quarter_arc_is_a_quarter_circumference() {
+        let quarter = CurveSegment::Arc {
+            center: p(0.0, 0.0),
+            radius: 20.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            edge: Default::default(),
+        };
+        let length = path_length(&p(20.0, 0.0), &[quarter], false);
+        assert!((length.total - 10.0 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn straight_cubic_is_its_chord() {
+        // Controls on the chord but unevenly spaced, so the curve speeds up along it.
+        let cubic = CurveSegment::CubicBezier {
+            control1: p(10.0, 0.0),
+            control2: p(80.0, 0.0),
+            end: p(100.0, 0.0),
+            edge: Default::default(),
+        };
+        let length = path_length(&p(0.0, 0.0), &[cubic], false);
+        assert!((length.total - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn closed_paths_count_the_closing_edge() {
+        let mut piece = create_default("Piece");
+        piece.outline = vec![line(100.0, 0.0), line(100.0, 100.0), line(0.0, 100.0)];
+        piece.internal_lines = vec![vec![line(50.0, 50.0)]];
+        piece.cut_outs = vec![CutOut {
+            origin: p(10.0, 10.0),
+            outline: vec![line(20.0, 10.0), line(20.0, 20.0)],
+        }];
+        let measured = measure_piece(&piece);
+        assert_eq!(measured.perimeter.segments, [100.0, 100.0, 100.0]);
+        assert_eq!(measured.perimeter.closing, 100.0);
+        assert_eq!(measured.perimeter.total, 400.0);
+        // Internal lines start at the piece origin and stay open.
+        assert!((measured.internal_lines[0].total - 50.0 * 2f64.sqrt()).abs() < 1e-9);
+        assert!((measured.cut_outs[0].total - (20.0 + 10.0 * 2f64.sqrt())).abs() < 1e-9);
+    }
+}
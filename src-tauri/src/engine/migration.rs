@@ -0,0 +1,269 @@
+use serde_json::Value;
+
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{CurveSegment, Notch, NotchKind, OutlinePosition, Point2D};
+
+use super::validation::{CLOSE_TOLERANCE, DEGENERATE_LENGTH};
+
+/// Project file format written by this build. Bump it, and add a step to `MIGRATIONS`,
+/// whenever saved data changes in a way older builds would misread.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// `MIGRATIONS[i]` upgrades a project from format version `i + 1` to `i + 2`.
+const MIGRATIONS: [fn(&mut Value); CURRENT_VERSION as usize - 1] = [v1_to_v2];
+
+/// Depth given to notches loaded from files that stored them as bare points.
+const LEGACY_NOTCH_DEPTH_MM: f64 = 6.0;
+
+/// Upgrade a parsed project file in place to `CURRENT_VERSION`, one version at a time.
+pub fn migrate(project: &mut Value) -> AppResult<()> {
+    let version = project
+        .get("version")
+        .and_then(Value::as_u64)
+        .filter(|&v| v >= 1)
+        .ok_or_else(|| AppError::invalid_project("Project file has no valid format version"))?;
+    if version > u64::from(CURRENT_VERSION) {
+        let saved_by = project
+            .get("app_version")
+            .and_then(Value::as_str)
+            .map(|v| format!(" by tcad {v}"))
+            .unwrap_or_default();
+        return Err(AppError::invalid_project(format!(
+            "This project was saved{saved_by} in file format {version}, but this version \
+             of tcad only reads up to format {CURRENT_VERSION}. Update tcad to open it."
+        )));
+    }
+
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(project);
+    }
+    project["version"] = Value::from(CURRENT_VERSION);
+    Ok(())
+}
+
+/// Version 2 stores notches anchored to the outline, where version 1 files could also
+/// hold them as bare points. It also drops `PieceMetadata.mirror`: a piece cut on the fold
+/// names its `fold_edge` instead.
+fn v1_to_v2(project: &mut Value) {
+    for_each_saved(project, "pieces", |piece| {
+        anchor_legacy_notches(piece);
+        fold_mirrored_piece(piece);
+    });
+}
+
+/// Call `f` on every saved copy of a piece or seam, `kind` being `"pieces"` or `"seams"`:
+/// the current ones, those in checkpoints and those recorded in the undo history.
+fn for_each_saved(project: &mut Value, kind: &str, mut f: impl FnMut(&mut Value)) {
+    array_items(project, kind).for_each(&mut f);
+    for checkpoint in array_items(project, "checkpoints") {
+        array_items(checkpoint, kind).for_each(&mut f);
+    }
+    for entry in project
+        .pointer_mut("/history/entries")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+    {
+        // Undo steps map ids to the earlier state, which is null for something created.
+        let Some(saved) = entry.get_mut("delta").and_then(|d| d.get_mut(kind)) else {
+            continue;
+        };
+        saved
+            .as_object_mut()
+            .into_iter()
+            .flat_map(|items| items.values_mut())
+            .filter(|item| !item.is_null())
+            .for_each(&mut f);
+    }
+}
+
+fn array_items<'a>(value: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    value
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+/// Drop the mirror flag. A piece marked mirrored with no fold edge yet is folded along
+/// its longest straight edge, counting the implicit closing edge.
+fn fold_mirrored_piece(piece: &mut Value) {
+    let Some(mirror) = piece
+        .get_mut("metadata")
+        .and_then(Value::as_object_mut)
+        .and_then(|m| m.remove("mirror"))
+    else {
+        return;
+    };
+    if mirror != Value::Bool(true) || piece.get("fold_edge").is_some_and(|e| !e.is_null()) {
+        return;
+    }
+    let (Ok(origin), Ok(outline)) = (
+        serde_json::from_value::<Point2D>(piece["origin"].clone()),
+        serde_json::from_value::<Vec<CurveSegment>>(piece["outline"].clone()),
+    ) else {
+        return;
+    };
+
+    let fold_edge = curve::resolve_closed(&origin, &outline, CLOSE_TOLERANCE)
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| matches!(c, Curve::Line { .. }))
+        .map(|(i, c)| (i, c.length()))
+        .filter(|&(_, length)| length >= DEGENERATE_LENGTH)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i);
+    if let Some(edge) = fold_edge {
+        piece["fold_edge"] = Value::from(edge);
+    }
+}
+
+/// Replace notches stored as points with slits anchored at the nearest outline position.
+/// Notches that are already anchored are kept as they are.
+fn anchor_legacy_notches(piece: &mut Value) {
+    let Some(notches) = piece.get("notches").and_then(Value::as_array) else {
+        return;
+    };
+    if !notches.iter().any(|n| as_point(n).is_some()) {
+        return;
+    }
+    let (Ok(origin), Ok(outline)) = (
+        serde_json::from_value::<Point2D>(piece["origin"].clone()),
+        serde_json::from_value::<Vec<CurveSegment>>(piece["outline"].clone()),
+    ) else {
+        return;
+    };
+
+    let curves = curve::resolve_closed(&origin, &outline, CLOSE_TOLERANCE);
+    let anchored: Vec<Value> = notches
+        .iter()
+        .filter_map(|n| {
+            let Some(p) = as_point(n) else {
+                return Some(n.clone());
+            };
+            // A point with no outline to anchor to is dropped.
+            let (segment, t) = curve::closest_position(&curves, Vec2::from(&p))?;
+            serde_json::to_value(Notch {
+                position: OutlinePosition { segment, t },
+                kind: NotchKind::Slit,
+                depth_mm: LEGACY_NOTCH_DEPTH_MM,
+                width_mm: 0.0,
+                angle: 0.0,
+            })
+            .ok()
+        })
+        .collect();
+    piece["notches"] = Value::from(anchored);
+}
+
+/// A notch saved as a bare point.
+fn as_point(notch: &Value) -> Option<Point2D> {
+    serde_json::from_value(notch.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn square_piece(notches: Value, metadata: Value) -> Value {
+        json!({
+            "id": "p",
+            "origin": { "x": 0.0, "y": 0.0 },
+            "outline": [
+                { "type": "Line", "end": { "x": 100.0, "y": 0.0 } },
+                { "type": "Line", "end": { "x": 100.0, "y": 100.0 } },
+                { "type": "Line", "end": { "x": 0.0, "y": 100.0 } },
+            ],
+            "notches": notches,
+            "metadata": metadata,
+        })
+    }
+
+    /// A version 1 project holding `piece` now, in a checkpoint and in an undo step.
+    fn saved_everywhere(piece: Value) -> Value {
+        json!({
+            "version": 1,
+            "pieces": [piece.clone()],
+            "checkpoints": [{ "pieces": [piece.clone()], "seams": [] }],
+            "history": {
+                "entries": [{ "delta": { "pieces": { "p": piece, "q": null }, "seams": {} } }],
+                "position": 1,
+            },
+        })
+    }
+
+    fn saved_pieces(project: &Value) -> [&Value; 3] {
+        [
+            &project["pieces"][0],
+            &project["checkpoints"][0]["pieces"][0],
+            &project["history"]["entries"][0]["delta"]["pieces"]["p"],
+        ]
+    }
+
+    #[test]
+    fn point_notches_are_anchored_wherever_pieces_are_saved() {
+        let anchored = json!({
+            "position": { "segment": 1, "t": 0.25 },
+            "kind": "Castle",
+            "depth_mm": 4.0,
+            "width_mm": 3.0,
+        });
+        let notches = json!([anchored.clone(), { "x": 50.0, "y": 0.0 }]);
+        let mut project = saved_everywhere(square_piece(notches, json!({})));
+        migrate(&mut project).unwrap();
+
+        assert_eq!(project["version"], CURRENT_VERSION);
+        for saved in saved_pieces(&project) {
+            let notches = saved["notches"].as_array().unwrap();
+            assert_eq!(notches.len(), 2);
+            assert_eq!(notches[0], anchored);
+            assert_eq!(notches[1]["position"], json!({ "segment": 0, "t": 0.5 }));
+            assert_eq!(notches[1]["kind"], "Slit");
+        }
+    }
+
+    #[test]
+    fn mirrored_pieces_are_cut_on_the_fold() {
+        // The implicit closing edge runs 150 mm back up to the origin, the longest edge.
+        let mut piece = square_piece(json!([]), json!({ "cut_quantity": 2, "mirror": true }));
+        piece["outline"][2]["end"] = json!({ "x": 0.0, "y": 150.0 });
+        let mut project = saved_everywhere(piece);
+        migrate(&mut project).unwrap();
+
+        for saved in saved_pieces(&project) {
+            assert_eq!(saved["metadata"], json!({ "cut_quantity": 2 }));
+            assert_eq!(saved["fold_edge"], 3);
+        }
+    }
+
+    #[test]
+    fn mirror_flags_keep_an_existing_fold_edge() {
+        let mut piece = square_piece(json!([]), json!({ "cut_quantity": 1, "mirror": true }));
+        piece["fold_edge"] = json!(1);
+        let unmirrored = square_piece(json!([]), json!({ "cut_quantity": 1, "mirror": false }));
+        let mut project = json!({ "version": 1, "pieces": [piece, unmirrored] });
+        migrate(&mut project).unwrap();
+
+        let pieces = &project["pieces"];
+        assert_eq!(pieces[0]["fold_edge"], 1);
+        assert!(pieces[1].get("fold_edge").is_none());
+        assert_eq!(pieces[1]["metadata"], json!({ "cut_quantity": 1 }));
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let mut project = json!({ "version": CURRENT_VERSION + 1, "app_version": "9.9.9" });
+        let error = migrate(&mut project).unwrap_err();
+        assert!(matches!(error, AppError::InvalidProject { .. }));
+        assert!(error.message().contains("tcad 9.9.9"));
+    }
+
+    #[test]
+    fn missing_version_is_refused() {
+        assert!(migrate(&mut json!({ "pieces": [] })).is_err());
+    }
+}
@@ -1,6 +1,21 @@
+pub mod assets;
+pub mod batch;
+pub mod bundle;
+pub mod checkpoint;
+pub mod document;
 pub mod export;
+pub mod fold;
 pub mod history;
+pub mod marking;
+pub mod measure;
+pub mod migration;
+pub mod notch;
 pub mod pattern_piece;
 pub mod project;
-pub mod seam;
+pub mod repair;
+pub mod seam_allowance;
+pub mod seam_match;
+pub mod seams;
+pub mod storage;
+pub mod transform;
 pub mod validation;
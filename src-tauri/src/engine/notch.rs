@@ -0,0 +1,145 @@
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::vec2::Vec2;
+use crate::types::pattern::{Notch, NotchKind};
+
+/// Chords used for the rounded end of a U notch.
+const U_ARC_STEPS: usize = 8;
+
+/// Polylines drawing each notch on an outline made of `curves` (including any closing edge),
+/// in the same frame as the curves. Notches anchored to a missing segment are skipped.
+pub fn notch_polylines(curves: &[Curve], notches: &[Notch]) -> Vec<Vec<Vec2>> {
+    // Inward is to the left of the direction of travel for positive winding.
+    let side = if curve::closed_signed_area(curves) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+
+    notches
+        .iter()
+        .filter_map(|notch| {
+            let c = curves.get(notch.position.segment)?;
+            let t = notch.position.t.clamp(0.0, 1.0);
+            let base = c.point_at(t);
+            let inward = (c.tangent_at(t).perpendicular() * side).rotate(notch.angle);
+            Some(notch_shape(base, inward, notch))
+        })
+        .collect()
+}
+
+/// The outline of one notch cut at `base`, running `depth_mm` along `inward`.
+fn notch_shape(base: Vec2, inward: Vec2, notch: &Notch) -> Vec<Vec2> {
+    let depth = notch.depth_mm.max(0.0);
+    let half = notch.width_mm.max(0.0) * 0.5;
+    let across = inward.perpendicular() * half;
+    let tip = base + inward * depth;
+
+    match notch.kind {
+        NotchKind::Slit => vec![base, tip],
+        // Drawn as one stroke: down the stem, then across the bar.
+        NotchKind::T => vec![base, tip, tip - across, tip + across],
+        NotchKind::V => vec![base - across, tip, base + across],
+        NotchKind::Castle => vec![base - across, tip - across, tip + across, base + across],
+        NotchKind::U => {
+            let radius = half.min(depth);
+            let center = base + inward * (depth - radius);
+            let ax = across.normalize();
+            let mut points = vec![base - across];
+            points.extend((0..=U_ARC_STEPS).map(|i| {
+                let a = std::f64::consts::PI * i as f64 / U_ARC_STEPS as f64;
+                center + (inward * a.sin() - ax * a.cos()) * radius
+            }));
+            points.push(base + across);
+            points
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pattern::OutlinePosition;
+
+    /// A counter-clockwise 100 mm square starting at the origin.
+    fn square() -> Vec<Curve> {
+        let corners = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        (0..4)
+            .map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                Curve::Line {
+                    from: Vec2::new(a.0, a.1),
+                    to: Vec2::new(b.0, b.1),
+                }
+            })
+            .collect()
+    }
+
+    /// A 6 mm deep, 4 mm wide notch halfway along the bottom edge.
+    fn notch(kind: NotchKind) -> Notch {
+        Notch {
+            position: OutlinePosition { segment: 0, t: 0.5 },
+            kind,
+            depth_mm: 6.0,
+            width_mm: 4.0,
+            angle: 0.0,
+        }
+    }
+
+    fn shape(curves: &[Curve], kind: NotchKind) -> Vec<(f64, f64)> {
+        let round = |v: f64| (v * 1e6).round() / 1e6;
+        notch_polylines(curves, &[notch(kind)])[0]
+            .iter()
+            .map(|p| (round(p.x), round(p.y)))
+            .collect()
+    }
+
+    #[test]
+    fn each_kind_is_cut_inward() {
+        let square = square();
+        assert_eq!(shape(&square, NotchKind::Slit), [(50.0, 0.0), (50.0, 6.0)]);
+        assert_eq!(
+            shape(&square, NotchKind::T),
+            [(50.0, 0.0), (50.0, 6.0), (52.0, 6.0), (48.0, 6.0)]
+        );
+        assert_eq!(
+            shape(&square, NotchKind::V),
+            [(52.0, 0.0), (50.0, 6.0), (48.0, 0.0)]
+        );
+        assert_eq!(
+            shape(&square, NotchKind::Castle),
+            [(52.0, 0.0), (52.0, 6.0), (48.0, 6.0), (48.0, 0.0)]
+        );
+
+        // A U runs straight in, then round a 2 mm radius whose far side is at the depth.
+        let u = shape(&square, NotchKind::U);
+        assert_eq!(u.len(), U_ARC_STEPS + 3);
+        assert_eq!((u[0], u[u.len() - 1]), ((52.0, 0.0), (48.0, 0.0)));
+        assert!(u.contains(&(50.0, 6.0)));
+        assert!(u.iter().all(|&(_, y)| (0.0..=6.0).contains(&y)));
+    }
+
+    #[test]
+    fn clockwise_outlines_are_cut_inward_too() {
+        let clockwise: Vec<Curve> = square().iter().rev().map(Curve::reversed).collect();
+        // Segment 0 now runs down the left side, from (0, 100) to (0, 0).
+        assert_eq!(
+            shape(&clockwise, NotchKind::Slit),
+            [(0.0, 50.0), (6.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn angle_tilts_the_notch() {
+        let mut tilted = notch(NotchKind::Slit);
+        tilted.angle = std::f64::consts::FRAC_PI_2;
+        let tip = notch_polylines(&square(), &[tilted])[0][1];
+        assert!((tip.x - 44.0).abs() < 1e-9 && tip.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn notches_off_the_outline_are_skipped() {
+        let mut stray = notch(NotchKind::Slit);
+        stray.position.segment = 4;
+        assert!(notch_polylines(&square(), &[stray]).is_empty());
+    }
+}
@@ -1,80 +1,122 @@
-use std::collections::HashMap;
-use std::sync::Mutex;
-
 use uuid::Uuid;
 
-use crate::types::pattern::{PatternPieceData, PatternPieceId, PieceMetadata, Point2D};
-
-use super::history;
-
-/// In-memory store for pattern pieces.
-static PIECES: std::sync::LazyLock<Mutex<HashMap<PatternPieceId, PatternPieceData>>> =
-    std::sync::LazyLock::new(|| Mutex::new(HashMap::new()));
-
-/// Take a snapshot of the current store (for undo/redo).
-pub fn snapshot() -> HashMap<PatternPieceId, PatternPieceData> {
-    let store = PIECES.lock().unwrap();
-    store.clone()
-}
-
-/// Restore the store from a snapshot.
-pub fn restore(snap: HashMap<PatternPieceId, PatternPieceData>) {
-    let mut store = PIECES.lock().unwrap();
-    *store = snap;
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+use crate::types::pattern::{
+    OutlinePosition, PatternPieceData, PatternPieceId, PieceMetadata, Point2D,
+};
+
+use super::document::Project;
+use super::history::{self, Delta};
+use super::seams;
+
+/// Put one piece back to a recorded state, removing it for `None` (for undo/redo).
+pub fn restore_entry(project: &mut Project, id: &PatternPieceId, piece: Option<PatternPieceData>) {
+    let previous = match piece {
+        Some(piece) => project.pieces.insert(id.clone(), piece),
+        None => project.pieces.remove(id),
+    };
+    let id = id.clone();
+    match (previous.is_some(), project.pieces.contains_key(&id)) {
+        (false, true) => project.notify(ChangeEvent::PieceCreated { id }),
+        (true, true) => project.notify(ChangeEvent::PieceUpdated { id }),
+        (true, false) => project.notify(ChangeEvent::PieceDeleted { id }),
+        (false, false) => {}
+    }
 }
 
-/// Replace all pieces in the store (used by project load).
-pub fn replace_all(pieces: Vec<PatternPieceData>) {
-    let mut store = PIECES.lock().unwrap();
-    store.clear();
-    for piece in pieces {
-        store.insert(piece.id.clone(), piece);
-    }
+/// Replace all pieces in the project (used by project load).
+pub fn replace_all(project: &mut Project, pieces: Vec<PatternPieceData>) {
+    project.pieces = pieces
+        .into_iter()
+        .map(|piece| (piece.id.clone(), piece))
+        .collect();
 }
 
-pub fn create(mut piece: PatternPieceData) -> Result<PatternPieceId, String> {
+pub fn create(project: &mut Project, mut piece: PatternPieceData) -> AppResult<PatternPieceId> {
     let id = Uuid::new_v4().to_string();
     piece.id = id.clone();
 
-    history::push_snapshot(snapshot());
+    let delta = Delta::default().piece(project, &id);
+    history::record(project, format!("Create {}", piece.name), delta);
 
-    let mut store = PIECES.lock().map_err(|e| format!("Lock error: {e}"))?;
-    store.insert(id.clone(), piece);
+    project.pieces.insert(id.clone(), piece);
+    project.notify(ChangeEvent::PieceCreated { id: id.clone() });
     Ok(id)
 }
 
-pub fn update(id: PatternPieceId, piece: PatternPieceData) -> Result<(), String> {
-    history::push_snapshot(snapshot());
+pub fn update(project: &mut Project, id: PatternPieceId, piece: PatternPieceData) -> AppResult<()> {
+    let label = format!("Edit {}", piece.name);
+    update_labelled(project, id, piece, label)
+}
 
-    let mut store = PIECES.lock().map_err(|e| format!("Lock error: {e}"))?;
-    if !store.contains_key(&id) {
-        return Err(format!("Piece not found: {id}"));
-    }
-    store.insert(id, piece);
+/// Replace a piece, labelling the undo step `label` rather than a generic edit.
+pub fn update_labelled(
+    project: &mut Project,
+    id: PatternPieceId,
+    piece: PatternPieceData,
+    label: String,
+) -> AppResult<()> {
+    get(project, &id)?;
+    let delta = Delta::default().piece(project, &id);
+    history::record(project, label, delta);
+
+    project.pieces.insert(id.clone(), piece);
+    project.notify(ChangeEvent::PieceUpdated { id });
     Ok(())
 }
 
-pub fn delete(id: &PatternPieceId) -> Result<(), String> {
-    history::push_snapshot(snapshot());
+/// Replace a piece after an edit that rebuilt its outline, such as a repair or a mirroring
+/// transform, moving its seams along in the same undo step. `anchors` are the positions from
+/// [`seams::anchors_on_piece`] carried through the edit, and `reversed` says the outline now
+/// runs the other way.
+pub fn update_reworked(
+    project: &mut Project,
+    id: PatternPieceId,
+    piece: PatternPieceData,
+    label: String,
+    anchors: &[OutlinePosition],
+    reversed: bool,
+) -> AppResult<()> {
+    get(project, &id)?;
+    let delta = seams::ids_for_piece(project, &id)
+        .iter()
+        .fold(Delta::default().piece(project, &id), |delta, seam_id| {
+            delta.seam(project, seam_id)
+        });
+    history::record(project, label, delta);
+
+    project.pieces.insert(id.clone(), piece);
+    project.notify(ChangeEvent::PieceUpdated { id: id.clone() });
+    seams::move_anchors(project, &id, anchors, reversed);
+    Ok(())
+}
 
-    let mut store = PIECES.lock().map_err(|e| format!("Lock error: {e}"))?;
-    store
-        .remove(id)
-        .ok_or_else(|| format!("Piece not found: {id}"))?;
+pub fn delete(project: &mut Project, id: &PatternPieceId) -> AppResult<()> {
+    let piece = get(project, id)?;
+    let delta = seams::ids_for_piece(project, id)
+        .iter()
+        .fold(Delta::default().piece(project, id), |delta, seam_id| {
+            delta.seam(project, seam_id)
+        });
+    history::record(project, format!("Delete {}", piece.name), delta);
+
+    project.pieces.remove(id);
+    project.notify(ChangeEvent::PieceDeleted { id: id.clone() });
+    seams::remove_for_piece(project, id);
     Ok(())
 }
 
-pub fn get(id: &PatternPieceId) -> Result<PatternPieceData, String> {
-    let store = PIECES.lock().map_err(|e| format!("Lock error: {e}"))?;
-    store
+pub fn get(project: &Project, id: &PatternPieceId) -> AppResult<PatternPieceData> {
+    project
+        .pieces
         .get(id)
         .cloned()
-        .ok_or_else(|| format!("Piece not found: {id}"))
+        .ok_or_else(|| AppError::not_found(format!("Piece not found: {id}")).with_piece(id))
 }
 
-pub fn get_all() -> Result<Vec<PatternPieceData>, String> {
-    let store = PIECES.lock().map_err(|e| format!("Lock error: {e}"))?;
-    Ok(store.values().cloned().collect())
+pub fn get_all(project: &Project) -> Vec<PatternPieceData> {
+    project.pieces.values().cloned().collect()
 }
 
 /// Create a default empty pattern piece with a given name.
@@ -89,6 +131,9 @@ pub fn create_default(name: &str) -> PatternPieceData {
         seam_allowance_mm: 10.0,
         notches: Vec::new(),
         internal_lines: Vec::new(),
+        cut_outs: Vec::new(),
+        markings: Vec::new(),
+        fold_edge: None,
         metadata: PieceMetadata::default(),
     }
 }
@@ -1,93 +1,466 @@
+use std::cmp::Reverse;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::types::asset::Asset;
+use crate::types::checkpoint::Checkpoint;
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
 use crate::types::pattern::PatternPieceData;
+use crate::types::project::{ProjectFormat, RecoverySnapshot, RecoveryStatus};
+use crate::types::seam::Seam;
 
-use super::{history, pattern_piece};
+use super::document::{DocumentId, Project};
+use super::history::SavedHistory;
+use super::migration::{self, CURRENT_VERSION};
+use super::{assets, bundle, checkpoint, history, pattern_piece, seams, storage};
+
+/// Undo steps saved with a project when the caller doesn't choose a depth.
+pub const DEFAULT_HISTORY_DEPTH: usize = 50;
+
+/// Undo steps saved with every recovery snapshot, so a recovered document can be undone.
+pub const RECOVERY_HISTORY_DEPTH: usize = 50;
+
+/// Previous versions kept beside a project when the caller doesn't choose a count.
+pub const DEFAULT_BACKUP_COUNT: usize = 3;
+
+/// Recovery snapshots kept in the app data directory; older ones are deleted.
+const MAX_RECOVERY_SNAPSHOTS: usize = 5;
+const RECOVERY_DIR: &str = "recovery";
+/// Extension given to recovery snapshots that couldn't be read, so they are kept but no
+/// longer offered for recovery.
+const UNREADABLE_EXTENSION: &str = "unreadable.tcad";
+const UNREADABLE_SUFFIX: &str = ".unreadable.tcad";
+/// Where versions before timestamped snapshots kept their only recovery file.
+const LEGACY_RECOVERY_FILE: &str = "recovery.tcad";
 
 #[derive(Serialize, Deserialize)]
 pub struct ProjectFile {
     pub version: u32,
     pub app_version: String,
     pub pieces: Vec<PatternPieceData>,
+    #[serde(default)]
+    pub seams: Vec<Seam>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Undo history at the time of saving, if it was saved with the project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<SavedHistory>,
+    /// Embedded files. Bundles store them as separate entries beside the project JSON.
+    #[serde(skip)]
+    pub assets: Vec<Asset>,
 }
 
-pub fn save_project(path: &str) -> Result<(), String> {
-    let pieces = pattern_piece::get_all()?;
-    let project = ProjectFile {
-        version: 1,
+/// Snapshot `project` for saving, with up to `history_depth` undo steps (none for 0).
+fn to_file(project: &Project, history_depth: usize) -> ProjectFile {
+    ProjectFile {
+        version: CURRENT_VERSION,
         app_version: env!("CARGO_PKG_VERSION").to_string(),
-        pieces,
+        pieces: pattern_piece::get_all(project),
+        seams: seams::get_all(project),
+        checkpoints: project.checkpoints.clone(),
+        history: (history_depth > 0).then(|| history::save(project, history_depth)),
+        assets: project.assets.clone(),
+    }
+}
+
+/// Replace the contents of `project` with a loaded file, restoring its undo history if it
+/// has one.
+pub fn open_file(project: &mut Project, file: ProjectFile) {
+    pattern_piece::replace_all(project, file.pieces);
+    seams::replace_all(project, file.seams);
+    checkpoint::replace_all(project, file.checkpoints);
+    assets::replace_all(project, file.assets);
+    history::load(project, file.history.unwrap_or_default());
+    project.notify(ChangeEvent::ProjectLoaded);
+}
+
+/// Write `project` as plain JSON or as a zip bundle. Without a `format`, a bundle is
+/// written only when the project has assets to embed.
+fn write_project(
+    path: &Path,
+    project: &ProjectFile,
+    format: Option<ProjectFormat>,
+) -> AppResult<()> {
+    let format = format.unwrap_or(if project.assets.is_empty() {
+        ProjectFormat::Json
+    } else {
+        ProjectFormat::Bundle
+    });
+    let json = serde_json::to_string_pretty(project)
+        .map_err(|e| AppError::internal(format!("Serialize error: {e}")))?;
+    let data = match format {
+        ProjectFormat::Json if !project.assets.is_empty() => {
+            return Err(AppError::invalid_input(format!(
+                "The project has {} embedded files, which plain JSON can't hold. \
+                 Save it as a bundle.",
+                project.assets.len()
+            ))
+            .with_path(path));
+        }
+        ProjectFormat::Json => json.into_bytes(),
+        ProjectFormat::Bundle => bundle::write(&json, &project.assets)?,
     };
-    let json =
-        serde_json::to_string_pretty(&project).map_err(|e| format!("Serialize error: {e}"))?;
-    fs::write(path, json).map_err(|e| format!("Write error: {e}"))?;
-    Ok(())
+    storage::write_atomic(path, &data)
 }
 
-pub fn load_project(path: &str) -> Result<Vec<PatternPieceData>, String> {
-    let data = fs::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
-    let project: ProjectFile =
-        serde_json::from_str(&data).map_err(|e| format!("Parse error: {e}"))?;
-    if project.version != 1 {
-        return Err(format!("Unsupported project version: {}", project.version));
-    }
-    let pieces = project.pieces.clone();
-    pattern_piece::replace_all(project.pieces);
-    history::clear();
+/// Read a project saved either as plain JSON or as a zip bundle.
+fn read_project(path: &Path) -> AppResult<ProjectFile> {
+    let data = fs::read(path).map_err(|e| storage::read_error(e, path))?;
+    let project = if bundle::is_bundle(&data) {
+        bundle::read(&data).and_then(|(json, assets)| {
+            let mut project = parse_project(&json)?;
+            project.assets = assets;
+            Ok(project)
+        })
+    } else {
+        String::from_utf8(data)
+            .map_err(|e| AppError::invalid_project(format!("Read error: {e}")))
+            .and_then(|json| parse_project(&json))
+    };
+    project.map_err(|e| e.with_path(path))
+}
+
+/// Save `project` to `path`, first copying the version already there into the most
+/// recent of `backup_count` rolling backups.
+pub fn save_project(
+    project: &Project,
+    path: &str,
+    history_depth: usize,
+    format: Option<ProjectFormat>,
+    backup_count: usize,
+) -> AppResult<()> {
+    let file = to_file(project, history_depth);
+    let path = Path::new(path);
+    storage::rotate_backups(path, backup_count)?;
+    write_project(path, &file, format)
+}
+
+pub fn load_project(project: &mut Project, path: &str) -> AppResult<Vec<PatternPieceData>> {
+    let file = read_project(Path::new(path))?;
+    let pieces = file.pieces.clone();
+    open_file(project, file);
     Ok(pieces)
 }
 
-pub fn new_project() {
-    pattern_piece::replace_all(vec![]);
-    history::clear();
+pub fn new_project(project: &mut Project) {
+    *project = Project::default();
+    project.notify(ChangeEvent::ProjectLoaded);
 }
 
-pub fn save_recovery(app_data_dir: &Path) -> Result<(), String> {
-    let pieces = pattern_piece::get_all()?;
-    if pieces.is_empty() {
+/// Write a recovery snapshot of `project`, the document `document_id`. Each document keeps
+/// its own `MAX_RECOVERY_SNAPSHOTS` in a directory named after it, so windows editing
+/// different documents don't prune or clear each other's snapshots.
+pub fn save_recovery(
+    project: &Project,
+    app_data_dir: &Path,
+    document_id: &DocumentId,
+) -> AppResult<()> {
+    if project.pieces.is_empty() {
         return Ok(());
     }
-    fs::create_dir_all(app_data_dir).map_err(|e| format!("Dir error: {e}"))?;
-    let path = app_data_dir.join("recovery.tcad");
-    let project = ProjectFile {
-        version: 1,
-        app_version: env!("CARGO_PKG_VERSION").to_string(),
-        pieces,
-    };
-    let json =
-        serde_json::to_string_pretty(&project).map_err(|e| format!("Serialize error: {e}"))?;
-    fs::write(path, json).map_err(|e| format!("Write error: {e}"))?;
+    let file = to_file(project, RECOVERY_HISTORY_DEPTH);
+    let dir = app_data_dir.join(RECOVERY_DIR).join(document_id);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Dir error: {e}")).with_path(&dir))?;
+    let name = format!("recovery-{}.tcad", history::now_ms());
+    write_project(&dir.join(name), &file, None)?;
+
+    for (_, old) in snapshots_in(&dir)?.into_iter().skip(MAX_RECOVERY_SNAPSHOTS) {
+        let _ = fs::remove_file(old);
+    }
     Ok(())
 }
 
-pub fn check_recovery(app_data_dir: &Path) -> Result<Option<Vec<PatternPieceData>>, String> {
-    let path = app_data_dir.join("recovery.tcad");
-    if !path.exists() {
-        return Ok(None);
+/// The directories holding recovery files of documents that aren't in `open`, which are still
+/// being edited. A single recovery file left by an older version is moved in among them.
+fn recovery_dirs(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<Vec<PathBuf>> {
+    let dir = app_data_dir.join(RECOVERY_DIR);
+    let legacy = app_data_dir.join(LEGACY_RECOVERY_FILE);
+    if legacy.exists() {
+        let modified = fs::metadata(&legacy)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as u64);
+        fs::create_dir_all(&dir)
+            .map_err(|e| AppError::io(format!("Dir error: {e}")).with_path(&dir))?;
+        let _ = fs::rename(&legacy, dir.join(format!("recovery-{modified}.tcad")));
+    }
+    if !dir.exists() {
+        return Ok(Vec::new());
     }
-    let data = fs::read_to_string(&path).map_err(|e| format!("Read error: {e}"))?;
-    let project: ProjectFile = match serde_json::from_str(&data) {
-        Ok(p) => p,
-        Err(_) => {
-            // Invalid recovery file, clean up
-            let _ = fs::remove_file(&path);
-            return Ok(None);
+
+    // Files directly in the directory were written before documents had their own.
+    let entries = fs::read_dir(&dir).map_err(|e| storage::read_error(e, &dir))?;
+    let mut dirs = vec![dir];
+    for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+        let is_open = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| open.iter().any(|id| id == name));
+        if path.is_dir() && !is_open {
+            dirs.push(path);
         }
+    }
+    Ok(dirs)
+}
+
+/// Recovery snapshot files with their timestamps, newest first, leaving out those of the
+/// documents in `open`.
+fn recovery_snapshots(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<Vec<(u64, PathBuf)>> {
+    let mut snapshots = Vec::new();
+    for dir in recovery_dirs(app_data_dir, open)? {
+        snapshots.extend(snapshots_in(&dir)?);
+    }
+    snapshots.sort_by_key(|(timestamp, _)| Reverse(*timestamp));
+    Ok(snapshots)
+}
+
+/// Recovery files set aside as unreadable, leaving out those of the documents in `open`.
+fn unreadable_snapshots(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for dir in recovery_dirs(app_data_dir, open)? {
+        let entries = fs::read_dir(&dir).map_err(|e| storage::read_error(e, &dir))?;
+        files.extend(
+            entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.ends_with(UNREADABLE_SUFFIX))
+                }),
+        );
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The recovery snapshot files directly in `dir`, newest first.
+fn snapshots_in(dir: &Path) -> AppResult<Vec<(u64, PathBuf)>> {
+    let entries = fs::read_dir(dir).map_err(|e| storage::read_error(e, dir))?;
+    let mut snapshots: Vec<(u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let timestamp = name.strip_prefix("recovery-")?.strip_suffix(".tcad")?;
+            Some((timestamp.parse().ok()?, path))
+        })
+        .collect();
+    snapshots.sort_by_key(|(timestamp, _)| Reverse(*timestamp));
+    Ok(snapshots)
+}
+
+/// List the recovery snapshots left by documents that are no longer open, such as those of
+/// a previous session, newest first.
+///
+/// Snapshots that can't be read are renamed to `*.unreadable.tcad` rather than deleted.
+/// They are reported in `errors` every time until [`delete_unreadable_recovery`] removes
+/// them, or the user deletes them.
+pub fn check_recovery(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<RecoveryStatus> {
+    let mut status = RecoveryStatus {
+        snapshots: Vec::new(),
+        errors: Vec::new(),
     };
-    if project.pieces.is_empty() {
-        let _ = fs::remove_file(&path);
-        return Ok(None);
+    for (timestamp_ms, path) in recovery_snapshots(app_data_dir, open)? {
+        match read_project(&path) {
+            Ok(project) if project.pieces.is_empty() => {
+                let _ = fs::remove_file(&path);
+            }
+            Ok(project) => status.snapshots.push(RecoverySnapshot {
+                id: snapshot_id(app_data_dir, &path),
+                timestamp_ms,
+                piece_count: project.pieces.len(),
+            }),
+            Err(e) => {
+                if fs::rename(&path, path.with_extension(UNREADABLE_EXTENSION)).is_err() {
+                    status.errors.push(unreadable_message(&e, &path));
+                }
+            }
+        }
     }
-    Ok(Some(project.pieces))
+    for path in unreadable_snapshots(app_data_dir, open)? {
+        if let Err(e) = read_project(&path) {
+            status.errors.push(unreadable_message(&e, &path));
+        }
+    }
+    Ok(status)
 }
 
-pub fn clear_recovery(app_data_dir: &Path) -> Result<(), String> {
-    let path = app_data_dir.join("recovery.tcad");
-    if path.exists() {
-        fs::remove_file(path).map_err(|e| format!("Delete error: {e}"))?;
+fn unreadable_message(error: &AppError, path: &Path) -> String {
+    format!(
+        "Recovered work could not be read ({error}). It was kept at {}",
+        path.display()
+    )
+}
+
+/// Delete the recovery files set aside as unreadable by [`check_recovery`], leaving those of
+/// the documents in `open`.
+pub fn delete_unreadable_recovery(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<()> {
+    for path in unreadable_snapshots(app_data_dir, open)? {
+        fs::remove_file(&path)
+            .map_err(|e| AppError::io(format!("Delete error: {e}")).with_path(&path))?;
+    }
+    Ok(())
+}
+
+/// Read one recovery snapshot, or the newest readable one if `id` is `None`, then delete
+/// every snapshot of the document it came from. Snapshots of other documents are kept.
+pub fn take_recovery(
+    app_data_dir: &Path,
+    open: &[DocumentId],
+    id: Option<&str>,
+) -> AppResult<ProjectFile> {
+    let snapshots = recovery_snapshots(app_data_dir, open)?;
+    let (path, file) = match id {
+        Some(id) => {
+            let (_, path) = snapshots
+                .iter()
+                .find(|(_, path)| snapshot_id(app_data_dir, path) == id)
+                .ok_or_else(|| AppError::not_found(format!("Recovery snapshot not found: {id}")))?;
+            (path, read_project(path)?)
+        }
+        None => snapshots
+            .iter()
+            .find_map(|(_, path)| Some((path, read_project(path).ok()?)))
+            .ok_or_else(|| AppError::not_found("No recovery data found"))?,
+    };
+
+    let dir = path.parent().unwrap_or(app_data_dir);
+    for (_, sibling) in snapshots_in(dir)? {
+        fs::remove_file(&sibling)
+            .map_err(|e| AppError::io(format!("Delete error: {e}")).with_path(&sibling))?;
+    }
+    if dir != app_data_dir.join(RECOVERY_DIR) {
+        let _ = fs::remove_dir(dir);
+    }
+    Ok(file)
+}
+
+/// A snapshot's path within the recovery directory, which identifies it to the frontend.
+fn snapshot_id(app_data_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(app_data_dir.join(RECOVERY_DIR))
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Delete the recovery snapshots of every document that isn't in `open`.
+pub fn clear_recovery(app_data_dir: &Path, open: &[DocumentId]) -> AppResult<()> {
+    for (_, path) in recovery_snapshots(app_data_dir, open)? {
+        fs::remove_file(&path)
+            .map_err(|e| AppError::io(format!("Delete error: {e}")).with_path(&path))?;
+        if let Some(dir) = path
+            .parent()
+            .filter(|d| *d != app_data_dir.join(RECOVERY_DIR))
+        {
+            let _ = fs::remove_dir(dir);
+        }
     }
     Ok(())
 }
+
+/// Parse a project file, upgrading it from older format versions.
+fn parse_project(data: &str) -> AppResult<ProjectFile> {
+    let parse_error = |e: serde_json::Error| AppError::invalid_project(format!("Parse error: {e}"));
+    let mut value: Value = serde_json::from_str(data).map_err(parse_error)?;
+    migration::migrate(&mut value)?;
+    serde_json::from_value(value).map_err(parse_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn project_with_pieces(count: usize) -> Project {
+        let mut project = Project::default();
+        for _ in 0..count {
+            pattern_piece::create(&mut project, pattern_piece::create_default("Piece")).unwrap();
+        }
+        project
+    }
+
+    /// Save a snapshot, waiting first so each one gets its own timestamp.
+    fn save(project: &Project, dir: &Path, document_id: &str) {
+        thread::sleep(Duration::from_millis(2));
+        save_recovery(project, dir, &document_id.to_string()).unwrap();
+    }
+
+    #[test]
+    fn recovery_is_kept_per_document() {
+        let dir = std::env::temp_dir().join(format!("tcad-recovery-{}", uuid::Uuid::new_v4()));
+        // Document b is still open in another window.
+        let (a, open) = ("a".to_string(), ["b".to_string()]);
+        for count in 1..=MAX_RECOVERY_SNAPSHOTS + 1 {
+            save(&project_with_pieces(count), &dir, &a);
+        }
+        save(&project_with_pieces(1), &dir, &open[0]);
+
+        // Document b's snapshot didn't push out any of a's, and is left out while b is open.
+        let status = check_recovery(&dir, &open).unwrap();
+        assert_eq!(status.snapshots.len(), MAX_RECOVERY_SNAPSHOTS);
+        assert_eq!(status.snapshots[0].piece_count, MAX_RECOVERY_SNAPSHOTS + 1);
+        let status = check_recovery(&dir, &[]).unwrap();
+        assert_eq!(status.snapshots.len(), MAX_RECOVERY_SNAPSHOTS + 1);
+
+        // Restoring a's oldest snapshot deletes a's snapshots only.
+        let oldest = &status.snapshots[MAX_RECOVERY_SNAPSHOTS].id;
+        assert_eq!(
+            take_recovery(&dir, &[], Some(oldest)).unwrap().pieces.len(),
+            2
+        );
+        let status = check_recovery(&dir, &[]).unwrap();
+        assert_eq!(status.snapshots.len(), 1);
+        assert!(status.snapshots[0].id.starts_with('b'));
+
+        clear_recovery(&dir, &open).unwrap();
+        assert_eq!(check_recovery(&dir, &[]).unwrap().snapshots.len(), 1);
+        clear_recovery(&dir, &[]).unwrap();
+        assert!(check_recovery(&dir, &[]).unwrap().snapshots.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recovery_keeps_undo_history() {
+        let dir = std::env::temp_dir().join(format!("tcad-recovery-{}", uuid::Uuid::new_v4()));
+        let project = project_with_pieces(3);
+        save(&project, &dir, "a");
+
+        let recovered = take_recovery(&dir, &[], None).unwrap();
+        let history = recovered.history.unwrap();
+        assert_eq!(history.entries.len(), 3);
+        assert_eq!(history.position, 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_recovery_is_reported_until_deleted() {
+        let dir = std::env::temp_dir().join(format!("tcad-recovery-{}", uuid::Uuid::new_v4()));
+        save(&project_with_pieces(1), &dir, "a");
+        let broken = dir.join(RECOVERY_DIR).join("a").join("recovery-1.tcad");
+        fs::write(&broken, "not a project").unwrap();
+
+        for _ in 0..2 {
+            let status = check_recovery(&dir, &[]).unwrap();
+            assert_eq!(status.snapshots.len(), 1);
+            assert_eq!(status.errors.len(), 1);
+            assert!(status.errors[0].contains("recovery-1.unreadable.tcad"));
+        }
+        // Documents still open keep theirs.
+        delete_unreadable_recovery(&dir, &["a".to_string()]).unwrap();
+        assert_eq!(check_recovery(&dir, &[]).unwrap().errors.len(), 1);
+
+        delete_unreadable_recovery(&dir, &[]).unwrap();
+        let status = check_recovery(&dir, &[]).unwrap();
+        assert!(status.errors.is_empty());
+        assert_eq!(status.snapshots.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,678 @@
+use std::cmp::Ordering;
+
+use crate::geometry::bbox::BBox2D;
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::intersection;
+use crate::geometry::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{
+    CornerStyle, EdgeAttributes, OutlinePosition, PatternPieceData, PatternPieceId,
+};
+use crate::types::repair::{RepairChange, RepairKind, RepairReport};
+
+use super::document::Project;
+use super::validation::{CLOSE_TOLERANCE, DEGENERATE_LENGTH};
+use super::{pattern_piece, seams};
+
+/// Flattening tolerance (mm) used to locate loops and measure winding.
+const FLATTEN_TOLERANCE: f64 = 0.01;
+/// Self-intersection loops that fit in a box this many times the tolerance are removed.
+const LOOP_SIZE_FACTOR: f64 = 10.0;
+/// Upper bound on loop removals, in case each removal exposes another crossing.
+const MAX_LOOP_REMOVALS: usize = 64;
+const EPS: f64 = 1e-9;
+
+/// Resolved outline curves with the edge attributes of the segments they came from, and the
+/// positions anchored to them.
+struct Outline {
+    origin: Vec2,
+    curves: Vec<Curve>,
+    edges: Vec<EdgeAttributes>,
+    /// The piece's notch positions, followed by any other positions carried along.
+    anchors: Vec<OutlinePosition>,
+    /// The fold edge, or `None` once its segment has been removed.
+    fold_edge: Option<usize>,
+}
+
+impl Outline {
+    /// Move every anchor through `f`, which maps an old (segment, t) to a new one.
+    fn remap_anchors(&mut self, f: impl Fn(usize, f64) -> (usize, f64)) {
+        for anchor in &mut self.anchors {
+            (anchor.segment, anchor.t) = f(anchor.segment, anchor.t);
+        }
+    }
+
+    /// Move the fold edge through `f`, which maps an old segment index to a new one, or to
+    /// `None` if the segment is gone.
+    fn remap_fold_edge(&mut self, f: impl Fn(usize) -> Option<usize>) {
+        self.fold_edge = self.fold_edge.and_then(f);
+    }
+}
+
+/// Fix common defects in a piece outline:
+/// - close a gap of up to `tolerance_mm` between the last segment and the origin
+/// - remove small self-intersection loops (up to `LOOP_SIZE_FACTOR` × `tolerance_mm` across)
+/// - drop zero-length segments
+/// - merge consecutive collinear line segments with the same seam allowance
+/// - reverse the outline if it winds the opposite way to the editor (negative shoelace area)
+///
+/// The fold edge follows its segment, and is cleared if that segment is removed.
+/// `anchors` are other positions on the outline, such as the ends of seams. They are returned
+/// moved to the same places on the repaired outline, along with the repaired copy and a
+/// description of each change. The input is left untouched.
+pub fn repair_piece(
+    piece: &PatternPieceData,
+    tolerance_mm: f64,
+    anchors: Vec<OutlinePosition>,
+) -> AppResult<(RepairReport, Vec<OutlinePosition>)> {
+    if !tolerance_mm.is_finite() || tolerance_mm < 0.0 {
+        return Err(AppError::invalid_input(format!(
+            "Invalid repair tolerance: {tolerance_mm}"
+        )));
+    }
+
+    let mut outline = Outline {
+        origin: Vec2::from(&piece.origin),
+        curves: curve::resolve(&piece.origin, &piece.outline),
+        edges: piece.outline.iter().map(|s| s.edge().clone()).collect(),
+        anchors: piece
+            .notches
+            .iter()
+            .map(|n| n.position)
+            .chain(anchors)
+            .collect(),
+        fold_edge: piece.fold_edge,
+    };
+    let mut changes = Vec::new();
+
+    if !outline.curves.is_empty() {
+        close_gap(&mut outline, tolerance_mm, &mut changes);
+        remove_loops(&mut outline, tolerance_mm, &mut changes);
+        drop_degenerate(&mut outline, &mut changes);
+        merge_collinear(&mut outline, &mut changes);
+        fix_winding(&mut outline, &mut changes);
+    }
+    if let (Some(edge), None) = (piece.fold_edge, outline.fold_edge) {
+        changes.push(RepairChange {
+            kind: RepairKind::ClearedFoldEdge,
+            message: format!("Cleared fold edge {edge}, whose segment was removed"),
+            location: None,
+        });
+    }
+
+    let mut repaired = piece.clone();
+    repaired.fold_edge = outline.fold_edge;
+    repaired.outline = outline
+        .curves
+        .iter()
+        .zip(outline.edges)
+        .map(|(c, edge)| c.to_segment(edge))
+        .collect();
+    let mut anchors = outline.anchors.into_iter();
+    for (notch, position) in repaired.notches.iter_mut().zip(&mut anchors) {
+        notch.position = position;
+    }
+    let report = RepairReport {
+        piece: repaired,
+        changes,
+    };
+    Ok((report, anchors.collect()))
+}
+
+/// Repair a stored piece as one undo step, moving the ends of its seams along with the
+/// outline. Nothing is recorded when the piece needs no repair.
+pub fn apply_repair(
+    project: &mut Project,
+    id: &PatternPieceId,
+    tolerance_mm: f64,
+) -> AppResult<RepairReport> {
+    let piece = pattern_piece::get(project, id)?;
+    let anchors = seams::anchors_on_piece(project, id);
+    let (report, anchors) = repair_piece(&piece, tolerance_mm, anchors)?;
+    if !report.changes.is_empty() {
+        let reversed = report
+            .changes
+            .iter()
+            .any(|c| c.kind == RepairKind::ReversedWinding);
+        let label = format!("Repair {}", piece.name);
+        pattern_piece::update_reworked(
+            project,
+            id.clone(),
+            report.piece.clone(),
+            label,
+            &anchors,
+            reversed,
+        )?;
+    }
+    Ok(report)
+}
+
+fn close_gap(outline: &mut Outline, tolerance: f64, changes: &mut Vec<RepairChange>) {
+    let Some(last) = outline.curves.last_mut() else {
+        return;
+    };
+    let end = last.end();
+    let gap = end.distance(&outline.origin);
+    if gap <= EPS || gap > tolerance {
+        return;
+    }
+
+    match last {
+        Curve::Line { to, .. } | Curve::Quadratic { to, .. } | Curve::Cubic { to, .. } => {
+            *to = outline.origin;
+            // The implicit closing edge is gone; anchors on it move to the snapped end.
+            let n = outline.curves.len();
+            outline.remap_anchors(|k, t| if k >= n { (n - 1, 1.0) } else { (k, t) });
+            outline.remap_fold_edge(|k| (k < n).then_some(k));
+        }
+        // An arc's end is fixed by its angles, so bridge the gap with a line instead, unless
+        // it is already close enough to count as closed.
+        Curve::Arc { .. } if gap <= CLOSE_TOLERANCE => return,
+        Curve::Arc { .. } => {
+            outline.curves.push(Curve::Line {
+                from: end,
+                to: outline.origin,
+            });
+            outline.edges.push(EdgeAttributes::default());
+        }
+    }
+    changes.push(RepairChange {
+        kind: RepairKind::ClosedGap,
+        message: format!("Closed a {gap:.2} mm gap between the last segment and the origin"),
+        location: Some(end.into()),
+    });
+}
+
+/// The outline flattened into a polygon. Each point remembers the segment and parameter it
+/// was sampled at, so polygon crossings can be mapped back onto the curves.
+struct Flattened {
+    points: Vec<Vec2>,
+    samples: Vec<(usize, f64)>,
+    closed: bool,
+}
+
+impl Flattened {
+    fn new(curves: &[Curve]) -> Self {
+        let mut points = vec![curves[0].start()];
+        let mut samples = vec![(0, 0.0)];
+        for (k, c) in curves.iter().enumerate() {
+            let pts = c.flatten(FLATTEN_TOLERANCE);
+            let steps = (pts.len() - 1) as f64;
+            for (m, p) in pts.into_iter().enumerate() {
+                if points.last().is_some_and(|last| last.distance(&p) > EPS) {
+                    points.push(p);
+                    samples.push((k, m as f64 / steps));
+                }
+            }
+        }
+        // Closed as the rest of the engine sees it, so nearly closed outlines count too.
+        let closed =
+            points.len() > 2 && points[0].distance(&points[points.len() - 1]) <= CLOSE_TOLERANCE;
+        if closed {
+            points.pop();
+        }
+        Self {
+            points,
+            samples,
+            closed,
+        }
+    }
+
+    /// Segment index and curve parameter at parameter `s` along polygon edge `e`.
+    fn curve_param(&self, e: usize, s: f64) -> (usize, f64) {
+        let (seg, t1) = self.samples[e + 1];
+        let (prev_seg, prev_t) = self.samples[e];
+        let t0 = if prev_seg == seg { prev_t } else { 0.0 };
+        (seg, t0 + (t1 - t0) * s)
+    }
+}
+
+fn remove_loops(outline: &mut Outline, tolerance: f64, changes: &mut Vec<RepairChange>) {
+    let max_size = LOOP_SIZE_FACTOR * tolerance;
+    for _ in 0..MAX_LOOP_REMOVALS {
+        let flat = Flattened::new(&outline.curves);
+        let n = flat.points.len();
+        if n < 4 {
+            return;
+        }
+
+        let tiny_loop = intersection::polygon_self_intersections(&flat.points)
+            .into_iter()
+            // Edge n - 1 of an open outline is the implicit closing edge, not a real segment.
+            .filter(|&(_, j, _, _)| flat.closed || j < n - 1)
+            .find_map(|(i, j, t, u)| {
+                let x = flat.points[i].lerp(&flat.points[(i + 1) % n], t);
+                let mut bbox = BBox2D::from_points(&flat.points[i + 1..=j]);
+                bbox.expand_point(&x);
+                (bbox.width() <= max_size && bbox.height() <= max_size).then_some((i, j, t, u, x))
+            });
+        let Some((i, j, t, u, x)) = tiny_loop else {
+            return;
+        };
+
+        let (sa, ta) = flat.curve_param(i, t);
+        let (sb, tb) = flat.curve_param(j, u);
+        if sa > sb || (sa == sb && ta >= tb) {
+            return;
+        }
+
+        // Keep the curve up to the crossing, then resume from the crossing on the later curve.
+        let before = outline.curves[sa].split(ta).0;
+        let after = outline.curves[sb].split(tb).1;
+        let after_edge = outline.edges[sb].clone();
+        outline.curves.splice(sa..=sb, [before, after]);
+        outline.edges.splice(sa + 1..=sb, [after_edge]);
+        // Anchors on the removed loop move to the crossing.
+        outline.remap_anchors(|k, t| {
+            if k < sa {
+                (k, t)
+            } else if k == sa && t <= ta {
+                (sa, if ta > 0.0 { t / ta } else { 0.0 })
+            } else if k > sb {
+                (k - sb + sa + 1, t)
+            } else if k == sb && t >= tb {
+                (sa + 1, if tb < 1.0 { (t - tb) / (1.0 - tb) } else { 0.0 })
+            } else {
+                (sa, 1.0)
+            }
+        });
+        // The segments the crossing falls on are kept, but those wholly inside the loop go.
+        outline.remap_fold_edge(|k| {
+            if k <= sa {
+                Some(k)
+            } else if k >= sb {
+                Some(k - sb + sa + 1)
+            } else {
+                None
+            }
+        });
+
+        changes.push(RepairChange {
+            kind: RepairKind::RemovedLoop,
+            message: "Removed a small self-intersection loop".to_string(),
+            location: Some(x.into()),
+        });
+    }
+}
+
+fn drop_degenerate(outline: &mut Outline, changes: &mut Vec<RepairChange>) {
+    let mut i = 0;
+    while i < outline.curves.len() && outline.curves.len() > 1 {
+        let c = outline.curves[i];
+        if c.flattened_length(FLATTEN_TOLERANCE) >= DEGENERATE_LENGTH {
+            i += 1;
+            continue;
+        }
+        // The vertex at the end of the dropped segment is also the end of the previous one.
+        let removed = outline.edges.remove(i);
+        outline.curves.remove(i);
+        if i > 0 && outline.edges[i - 1].corner.is_none() {
+            outline.edges[i - 1].corner = removed.corner;
+        }
+        outline.remap_anchors(|k, t| match k.cmp(&i) {
+            Ordering::Less => (k, t),
+            Ordering::Equal if i > 0 => (i - 1, 1.0),
+            Ordering::Equal => (0, 0.0),
+            Ordering::Greater => (k - 1, t),
+        });
+        outline.remap_fold_edge(|k| match k.cmp(&i) {
+            Ordering::Less => Some(k),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(k - 1),
+        });
+        changes.push(RepairChange {
+            kind: RepairKind::RemovedDegenerateSegment,
+            message: format!("Removed zero-length segment {i}"),
+            location: Some(c.start().into()),
+        });
+    }
+}
+
+fn merge_collinear(outline: &mut Outline, changes: &mut Vec<RepairChange>) {
+    let mut i = 0;
+    while i + 1 < outline.curves.len() {
+        let (Curve::Line { from, to: mid }, Curve::Line { to, .. }) =
+            (outline.curves[i], outline.curves[i + 1])
+        else {
+            i += 1;
+            continue;
+        };
+        let chord = to - from;
+        let deviation = (mid - from).cross(&chord).abs() / chord.length().max(EPS);
+        let same_allowance =
+            outline.edges[i].seam_allowance_mm == outline.edges[i + 1].seam_allowance_mm;
+        let forward = (mid - from).dot(&(to - mid)) > 0.0;
+        if deviation > CLOSE_TOLERANCE || !forward || !same_allowance {
+            i += 1;
+            continue;
+        }
+
+        // Keep anchors at the same distance along the merged line.
+        let (la, lb) = (mid.distance(&from), to.distance(&mid));
+        let total = la + lb;
+        outline.remap_anchors(|k, t| {
+            if k == i {
+                (i, t * la / total)
+            } else if k == i + 1 {
+                (i, (la + t * lb) / total)
+            } else if k > i + 1 {
+                (k - 1, t)
+            } else {
+                (k, t)
+            }
+        });
+        outline.remap_fold_edge(|k| Some(if k > i { k - 1 } else { k }));
+
+        outline.curves[i] = Curve::Line { from, to };
+        let next = outline.edges.remove(i + 1);
+        outline.curves.remove(i + 1);
+        outline.edges[i].corner = next.corner;
+        changes.push(RepairChange {
+            kind: RepairKind::MergedCollinearLines,
+            message: format!("Merged collinear line segments {i} and {}", i + 1),
+            location: Some(mid.into()),
+        });
+    }
+}
+
+fn fix_winding(outline: &mut Outline, changes: &mut Vec<RepairChange>) {
+    let flat = Flattened::new(&outline.curves);
+    if !flat.closed || curve::polygon_signed_area(&flat.points) >= 0.0 {
+        return;
+    }
+
+    let n = outline.curves.len();
+    // Corners live on the segment ending at the vertex. Once reversed, the vertex at the
+    // end of segment k is the one that used to end segment k - 1.
+    let edges: Vec<EdgeAttributes> = (0..n)
+        .rev()
+        .map(|k| EdgeAttributes {
+            seam_allowance_mm: outline.edges[k].seam_allowance_mm,
+            corner: outline.edges[(k + n - 1) % n].corner.map(mirror_corner),
+        })
+        .collect();
+    outline.curves = outline.curves.iter().rev().map(Curve::reversed).collect();
+    outline.edges = edges;
+    outline.remap_anchors(|k, t| (n - 1 - k.min(n - 1), 1.0 - t));
+    outline.remap_fold_edge(|k| Some(n - 1 - k.min(n - 1)));
+
+    changes.push(RepairChange {
+        kind: RepairKind::ReversedWinding,
+        message: "Reversed outline winding".to_string(),
+        location: None,
+    });
+}
+
+/// The corner style of a vertex once the outline through it is traversed the other way.
+pub fn mirror_corner(corner: CornerStyle) -> CornerStyle {
+    match corner {
+        CornerStyle::HemIncoming => CornerStyle::HemOutgoing,
+        CornerStyle::HemOutgoing => CornerStyle::HemIncoming,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::engine::validation::validate;
+    use crate::types::pattern::{CurveSegment, Notch, NotchKind, Point2D};
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: Point2D { x, y },
+            edge: EdgeAttributes::default(),
+        }
+    }
+
+    fn piece(outline: Vec<CurveSegment>) -> PatternPieceData {
+        let mut piece = create_default("Piece");
+        piece.outline = outline;
+        piece
+    }
+
+    fn kinds(report: &RepairReport) -> Vec<RepairKind> {
+        report.changes.iter().map(|c| c.kind).collect()
+    }
+
+    /// The end points of a repaired outline made of lines, rounded to 0.001 mm.
+    fn ends(piece: &PatternPieceData) -> Vec<(f64, f64)> {
+        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+        piece
+            .outline
+            .iter()
+            .map(|s| {
+                let CurveSegment::Line { end, .. } = s else {
+                    panic!("expected a line, found {s:?}");
+                };
+                (round(end.x), round(end.y))
+            })
+            .collect()
+    }
+
+    /// A counter-clockwise square with a zero-length segment after its first corner.
+    fn square_with_stub() -> PatternPieceData {
+        piece(vec![
+            line(100.0, 0.0),
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+        ])
+    }
+
+    #[test]
+    fn sound_outline_is_left_alone() {
+        let square = piece(vec![
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ]);
+        let (report, _) = repair_piece(&square, 0.5, vec![]).unwrap();
+        assert!(report.changes.is_empty());
+        assert!(repair_piece(&square, -1.0, vec![]).is_err());
+    }
+
+    #[test]
+    fn gap_within_tolerance_is_closed() {
+        let gapped = piece(vec![
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.3),
+        ]);
+        let (report, _) = repair_piece(&gapped, 0.5, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::ClosedGap]);
+        assert_eq!(ends(&report.piece)[3], (0.0, 0.0));
+        assert!(validate(&report.piece).is_empty());
+
+        let (report, _) = repair_piece(&gapped, 0.1, vec![]).unwrap();
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn gap_after_an_arc_is_bridged_with_a_line() {
+        let gapped = piece(vec![
+            line(100.0, 0.0),
+            CurveSegment::Arc {
+                center: Point2D { x: 50.0, y: 0.0 },
+                radius: 50.0,
+                start_angle: 0.0,
+                end_angle: 3.1,
+                edge: EdgeAttributes::default(),
+            },
+        ]);
+        let (report, _) = repair_piece(&gapped, 3.0, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::ClosedGap]);
+        assert_eq!(report.piece.outline.len(), 3);
+        assert!(validate(&report.piece).is_empty());
+    }
+
+    #[test]
+    fn small_loops_are_cut_out() {
+        // A 1 mm loop where the bottom edge doubles back over itself.
+        let looped = piece(vec![
+            line(51.0, 0.0),
+            line(51.0, 1.0),
+            line(50.0, 1.0),
+            line(50.0, -1.0),
+            line(100.0, -1.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ]);
+        let (report, _) = repair_piece(&looped, 0.5, vec![]).unwrap();
+        assert!(kinds(&report).contains(&RepairKind::RemovedLoop));
+        assert!(validate(&report.piece).is_empty());
+        assert_eq!(ends(&report.piece)[0], (50.0, 0.0));
+
+        let (report, _) = repair_piece(&looped, 0.05, vec![]).unwrap();
+        assert!(!kinds(&report).contains(&RepairKind::RemovedLoop));
+    }
+
+    #[test]
+    fn zero_length_segments_are_dropped() {
+        let mut stub = square_with_stub();
+        stub.outline[1].edge_mut().corner = Some(CornerStyle::Miter);
+        let (report, _) = repair_piece(&stub, 0.5, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::RemovedDegenerateSegment]);
+        assert_eq!(
+            ends(&report.piece),
+            [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]
+        );
+        // The dropped segment's corner belongs to the vertex it shared with segment 0.
+        assert_eq!(
+            report.piece.outline[0].edge().corner,
+            Some(CornerStyle::Miter)
+        );
+    }
+
+    #[test]
+    fn collinear_lines_are_merged() {
+        let split = piece(vec![
+            line(25.0, 0.0),
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ]);
+        let (report, _) = repair_piece(&split, 0.5, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::MergedCollinearLines]);
+        assert_eq!(ends(&report.piece)[0], (100.0, 0.0));
+        assert_eq!(report.piece.outline.len(), 4);
+
+        // Lines with different seam allowances stay separate.
+        let mut split = split;
+        split.outline[1].edge_mut().seam_allowance_mm = Some(20.0);
+        let (report, _) = repair_piece(&split, 0.5, vec![]).unwrap();
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn clockwise_outline_is_reversed() {
+        let mut clockwise = piece(vec![
+            line(0.0, 100.0),
+            line(100.0, 100.0),
+            line(100.0, 0.0),
+            line(0.0, 0.0),
+        ]);
+        clockwise.outline[0].edge_mut().corner = Some(CornerStyle::HemIncoming);
+        clockwise.outline[1].edge_mut().seam_allowance_mm = Some(30.0);
+        let (report, _) = repair_piece(&clockwise, 0.5, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::ReversedWinding]);
+        assert_eq!(
+            ends(&report.piece),
+            [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)]
+        );
+        // The top edge is now segment 2, ending at the vertex the hem corner was on.
+        let top = report.piece.outline[2].edge();
+        assert_eq!(top.corner, Some(CornerStyle::HemOutgoing));
+        assert_eq!(top.seam_allowance_mm, Some(30.0));
+    }
+
+    #[test]
+    fn nearly_closed_clockwise_outline_is_reversed() {
+        let clockwise = piece(vec![
+            line(0.0, 100.0),
+            line(100.0, 100.0),
+            line(100.0, 0.0),
+            line(0.005, 0.0),
+        ]);
+        let (report, _) = repair_piece(&clockwise, 0.0, vec![]).unwrap();
+        assert_eq!(kinds(&report), [RepairKind::ReversedWinding]);
+    }
+
+    #[test]
+    fn fold_edge_follows_its_segment() {
+        let mut piece = square_with_stub();
+        // The implicit closing edge, back down the left side.
+        piece.fold_edge = Some(4);
+        let (report, _) = repair_piece(&piece, 0.5, vec![]).unwrap();
+        assert_eq!(report.piece.fold_edge, Some(3));
+        assert!(report
+            .changes
+            .iter()
+            .all(|c| c.kind != RepairKind::ClearedFoldEdge));
+    }
+
+    #[test]
+    fn fold_edge_is_cleared_with_its_segment() {
+        let mut piece = square_with_stub();
+        piece.fold_edge = Some(1);
+        let (report, _) = repair_piece(&piece, 0.5, vec![]).unwrap();
+        assert_eq!(report.piece.fold_edge, None);
+        assert!(report
+            .changes
+            .iter()
+            .any(|c| c.kind == RepairKind::ClearedFoldEdge));
+    }
+
+    /// Where each notch of a piece is drawn.
+    fn notch_shapes(piece: &PatternPieceData) -> Vec<Vec<Vec2>> {
+        let curves = curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE);
+        crate::engine::notch::notch_polylines(&curves, &piece.notches)
+    }
+
+    fn slit(segment: usize, t: f64) -> Notch {
+        Notch {
+            position: OutlinePosition { segment, t },
+            kind: NotchKind::Slit,
+            depth_mm: 6.0,
+            width_mm: 0.0,
+            angle: 0.0,
+        }
+    }
+
+    #[test]
+    fn notches_stay_put_through_repairs() {
+        // Clockwise, with the top edge split in two and a zero-length stub on the right side.
+        let mut piece = piece(vec![
+            line(0.0, 100.0),
+            line(30.0, 100.0),
+            line(100.0, 100.0),
+            line(100.0, 0.0),
+            line(100.0, 0.0),
+            line(0.0, 0.0),
+        ]);
+        piece.notches = vec![slit(0, 0.5), slit(1, 0.5), slit(2, 0.25), slit(5, 0.75)];
+        let (report, _) = repair_piece(&piece, 0.5, vec![]).unwrap();
+        assert_eq!(
+            kinds(&report),
+            [
+                RepairKind::RemovedDegenerateSegment,
+                RepairKind::MergedCollinearLines,
+                RepairKind::ReversedWinding,
+            ]
+        );
+
+        let before = notch_shapes(&piece);
+        let after = notch_shapes(&report.piece);
+        assert_eq!(after.len(), before.len());
+        for (old, new) in before.iter().zip(&after) {
+            for (a, b) in old.iter().zip(new) {
+                assert!(a.distance(b) < 1e-9, "{a:?} moved to {b:?}");
+            }
+        }
+    }
+}
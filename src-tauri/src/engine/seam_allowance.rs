@@ -0,0 +1,219 @@
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::offset::{self, OffsetRun};
+use crate::geometry::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{CornerStyle, CurveSegment, EdgeAttributes, PatternPieceData, Point2D};
+
+use super::validation::CLOSE_TOLERANCE;
+
+/// Maximum deviation (mm) between a curved segment and its flattened polyline.
+const FLATTEN_TOLERANCE: f64 = 0.02;
+
+/// Compute the seam allowance (cutting line) of a piece by offsetting its closed outline
+/// outward. Each edge uses its own `seam_allowance_mm` override when set, and
+/// `allowance_mm` otherwise; an override of `0` keeps that edge on the fold line. The fold
+/// edge of a cut-on-fold piece always gets `0`, since the piece is cut on the fold there.
+///
+/// Curved segments are flattened to within `FLATTEN_TOLERANCE`. Convex corners are finished
+/// with the `corner` style of the edge ending there; the default miter (bevelled past the
+/// miter limit) makes two different widths meet where their offset lines cross. Where widths
+/// change along a smooth join the cutting line steps perpendicular to the edge. Loops produced
+/// by concave corners or by curves tighter than the allowance are trimmed away. The returned
+/// piece is a copy of the input whose outline is the cutting line, made of line segments.
+pub fn compute_seam_allowance(
+    piece: &PatternPieceData,
+    allowance_mm: f64,
+) -> AppResult<PatternPieceData> {
+    if !allowance_mm.is_finite() || allowance_mm < 0.0 {
+        return Err(AppError::invalid_input(format!(
+            "Invalid seam allowance: {allowance_mm}"
+        )));
+    }
+    if piece.outline.is_empty() {
+        return Err(
+            AppError::invalid_geometry("Piece has no outline segments").with_piece(&piece.id)
+        );
+    }
+
+    let curves = curve::resolve(&piece.origin, &piece.outline);
+    let mut runs = Vec::with_capacity(curves.len() + 1);
+    for (i, (c, seg)) in curves.iter().zip(&piece.outline).enumerate() {
+        let distance = if piece.fold_edge == Some(i) {
+            0.0
+        } else {
+            seg.edge().seam_allowance_mm.unwrap_or(allowance_mm)
+        };
+        if !distance.is_finite() || distance < 0.0 {
+            return Err(AppError::invalid_input(format!(
+                "Invalid seam allowance on segment {i}: {distance}"
+            ))
+            .with_piece(&piece.id)
+            .with_segment(i));
+        }
+        runs.push(OffsetRun {
+            points: c.flatten(FLATTEN_TOLERANCE),
+            distance,
+            corner: seg.edge().corner.unwrap_or_default(),
+        });
+    }
+
+    // An open outline is implicitly closed back to its origin.
+    let start = Vec2::from(&piece.origin);
+    let end = curves.last().map(Curve::end).unwrap_or(start);
+    if end.distance(&start) > CLOSE_TOLERANCE {
+        let on_fold = piece.fold_edge == Some(curves.len());
+        runs.push(OffsetRun {
+            points: vec![end, start],
+            distance: if on_fold { 0.0 } else { allowance_mm },
+            corner: CornerStyle::default(),
+        });
+    }
+
+    let cutting_line = offset::offset_closed(&runs).map_err(|e| e.with_piece(&piece.id))?;
+    Ok(with_polygon_outline(piece, &cutting_line))
+}
+
+/// Copy of `piece` whose outline is the closed polygon `points`. Notches are dropped, since
+/// they are anchored to segments of the original outline.
+fn with_polygon_outline(piece: &PatternPieceData, points: &[Vec2]) -> PatternPieceData {
+    let mut result = piece.clone();
+    result.notches.clear();
+    let Some(&first) = points.first() else {
+        result.outline.clear();
+        return result;
+    };
+    result.origin = Point2D::from(first);
+    result.outline = points[1..]
+        .iter()
+        .chain(std::iter::once(&first))
+        .map(|&p| CurveSegment::Line {
+            end: p.into(),
+            edge: EdgeAttributes::default(),
+        })
+        .collect();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::geometry::curve::polygon_signed_area;
+    use crate::geometry::intersection::polygon_self_intersections;
+
+    fn p(x: f64, y: f64) -> Point2D {
+        Point2D { x, y }
+    }
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: p(x, y),
+            edge: EdgeAttributes::default(),
+        }
+    }
+
+    fn vertices(piece: &PatternPieceData) -> Vec<Vec2> {
+        curve::resolve(&piece.origin, &piece.outline)
+            .iter()
+            .map(Curve::start)
+            .collect()
+    }
+
+    fn square() -> PatternPieceData {
+        let mut piece = create_default("Square");
+        piece.outline = vec![
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ];
+        piece
+    }
+
+    #[test]
+    fn open_outline_is_closed_back_to_the_origin() {
+        let mut piece = square();
+        piece.outline.pop();
+        let cut = compute_seam_allowance(&piece, 10.0).unwrap();
+        assert!((polygon_signed_area(&vertices(&cut)) - 14400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn edge_overrides_set_each_width() {
+        let mut piece = square();
+        piece.outline[0].edge_mut().seam_allowance_mm = Some(30.0);
+        piece.outline[3].edge_mut().seam_allowance_mm = Some(0.0);
+        let cut = compute_seam_allowance(&piece, 10.0).unwrap();
+        // 110 wide (0 on the left, 10 on the right) by 140 high (30 below, 10 above).
+        assert!((polygon_signed_area(&vertices(&cut)) - 110.0 * 140.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fold_edge_gets_no_allowance() {
+        let mut piece = square();
+        piece.outline[3].edge_mut().seam_allowance_mm = Some(20.0);
+        piece.fold_edge = Some(3);
+        let cut = compute_seam_allowance(&piece, 10.0).unwrap();
+        assert!((polygon_signed_area(&vertices(&cut)) - 110.0 * 120.0).abs() < 1e-6);
+
+        // The implicit closing edge can be the fold too.
+        piece.outline.pop();
+        let cut = compute_seam_allowance(&piece, 10.0).unwrap();
+        assert!((polygon_signed_area(&vertices(&cut)) - 110.0 * 120.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn curve_tighter_than_the_allowance_is_bridged() {
+        // A semicircular bite of radius 5 out of the top edge, under a 10 mm allowance.
+        let mut piece = square();
+        piece.outline = vec![
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(55.0, 100.0),
+            CurveSegment::Arc {
+                center: p(50.0, 100.0),
+                radius: 5.0,
+                start_angle: 0.0,
+                end_angle: -PI,
+                edge: EdgeAttributes::default(),
+            },
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ];
+        let cut = vertices(&compute_seam_allowance(&piece, 10.0).unwrap());
+        // The loop inside the bite is trimmed, leaving the top allowance straight across it.
+        assert_eq!(cut.len(), 4, "{cut:?}");
+        assert!((polygon_signed_area(&cut) - 14400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sharp_dip_leaves_no_loops() {
+        let mut piece = square();
+        piece.outline = vec![
+            line(40.0, 0.0),
+            CurveSegment::CubicBezier {
+                control1: p(50.0, 30.0),
+                control2: p(50.0, 30.0),
+                end: p(60.0, 0.0),
+                edge: EdgeAttributes::default(),
+            },
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ];
+        let cut = vertices(&compute_seam_allowance(&piece, 15.0).unwrap());
+        assert!(polygon_self_intersections(&cut).is_empty());
+        assert!(polygon_signed_area(&cut) > 130.0 * 115.0);
+    }
+
+    #[test]
+    fn negative_allowance_is_rejected() {
+        assert!(matches!(
+            compute_seam_allowance(&square(), -1.0),
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::geometry::curve::{self, Curve};
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{OutlinePosition, PatternPieceData};
+use crate::types::seam::{NotchPair, NotchPlacement, OutlineSection, SeamLengthMatch};
+
+use super::validation::CLOSE_TOLERANCE;
+
+/// Most notch pairs `match_sections` will place along one seam.
+pub const MAX_MATCH_NOTCHES: u32 = 100;
+
+/// The part of one outline curve covered by a section.
+struct Span {
+    curve: usize,
+    t0: f64,
+    t1: f64,
+    length: f64,
+}
+
+/// An outline section resolved against its piece.
+struct ResolvedSection {
+    curves: Vec<Curve>,
+    spans: Vec<Span>,
+    length: f64,
+    reversed: bool,
+}
+
+impl ResolvedSection {
+    fn new(piece: &PatternPieceData, section: &OutlineSection) -> AppResult<Self> {
+        let curves = curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE);
+        if curves.is_empty() {
+            return Err(AppError::invalid_geometry(format!(
+                "Piece {} has no outline segments",
+                piece.id
+            ))
+            .with_piece(&piece.id));
+        }
+        for p in [&section.start, &section.end] {
+            if p.segment >= curves.len() || !(0.0..=1.0).contains(&p.t) {
+                return Err(AppError::invalid_input(format!(
+                    "Invalid outline position on piece {}: segment {}, t = {}",
+                    piece.id, p.segment, p.t
+                ))
+                .with_piece(&piece.id)
+                .with_segment(p.segment));
+            }
+        }
+
+        let (s, e) = (section.start, section.end);
+        let spans = if (e.segment, e.t) > (s.segment, s.t) {
+            if s.segment == e.segment {
+                vec![(s.segment, s.t, e.t)]
+            } else {
+                let mut spans = vec![(s.segment, s.t, 1.0)];
+                spans.extend((s.segment + 1..e.segment).map(|k| (k, 0.0, 1.0)));
+                spans.push((e.segment, 0.0, e.t));
+                spans
+            }
+        } else if (e.segment, e.t) == (s.segment, s.t) {
+            return Err(AppError::invalid_input("Outline section is empty").with_piece(&piece.id));
+        } else {
+            let mut spans = vec![(s.segment, s.t, 1.0)];
+            spans.extend((s.segment + 1..curves.len()).map(|k| (k, 0.0, 1.0)));
+            spans.extend((0..e.segment).map(|k| (k, 0.0, 1.0)));
+            spans.push((e.segment, 0.0, e.t));
+            spans
+        };
+
+        let spans: Vec<Span> = spans
+            .into_iter()
+            .map(|(curve, t0, t1)| Span {
+                curve,
+                t0,
+                t1,
+                length: curves[curve].length_between(t0, t1),
+            })
+            .collect();
+        let length = spans.iter().map(|s| s.length).sum();
+        Ok(Self {
+            curves,
+            spans,
+            length,
+            reversed: section.reversed,
+        })
+    }
+
+    /// The point `fraction` of the way along the section, in walking direction.
+    fn placement_at(&self, fraction: f64) -> NotchPlacement {
+        let fraction = if self.reversed {
+            1.0 - fraction
+        } else {
+            fraction
+        };
+        let mut remaining = fraction * self.length;
+        let last = self.spans.len() - 1;
+        for (i, span) in self.spans.iter().enumerate() {
+            if remaining <= span.length || i == last {
+                let c = &self.curves[span.curve];
+                let t = c.t_at_length(span.t0, remaining).min(span.t1);
+                return NotchPlacement {
+                    position: OutlinePosition {
+                        segment: span.curve,
+                        t,
+                    },
+                    point: c.point_at(t).into(),
+                };
+            }
+            remaining -= span.length;
+        }
+        unreachable!("resolved sections always have at least one span")
+    }
+}
+
+/// Arc length (mm) of an outline section.
+pub fn section_length(piece: &PatternPieceData, section: &OutlineSection) -> AppResult<f64> {
+    Ok(ResolvedSection::new(piece, section)?.length)
+}
+
+/// Walk section `a` of `piece_a` against section `b` of `piece_b`: compare their lengths and
+/// place `notch_count` notch pairs that divide both sections into equal parts, so any ease
+/// is spread evenly between them. At most `MAX_MATCH_NOTCHES` pairs can be placed.
+pub fn match_sections(
+    piece_a: &PatternPieceData,
+    a: &OutlineSection,
+    piece_b: &PatternPieceData,
+    b: &OutlineSection,
+    notch_count: u32,
+) -> AppResult<SeamLengthMatch> {
+    if notch_count > MAX_MATCH_NOTCHES {
+        return Err(AppError::invalid_input(format!(
+            "Too many notches: {notch_count} (at most {MAX_MATCH_NOTCHES})"
+        )));
+    }
+    let a = ResolvedSection::new(piece_a, a)?;
+    let b = ResolvedSection::new(piece_b, b)?;
+
+    let difference = a.length - b.length;
+    let ease_percent = if b.length > 0.0 {
+        difference / b.length * 100.0
+    } else {
+        0.0
+    };
+    let notches = (1..=notch_count)
+        .map(|k| {
+            let fraction = f64::from(k) / (f64::from(notch_count) + 1.0);
+            NotchPair {
+                a: a.placement_at(fraction),
+                b: b.placement_at(fraction),
+            }
+        })
+        .collect();
+
+    Ok(SeamLengthMatch {
+        length_a: a.length,
+        length_b: b.length,
+        difference,
+        ease_percent,
+        notches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::types::pattern::{CurveSegment, Point2D};
+
+    fn square(id: &str, size: f64) -> PatternPieceData {
+        let mut piece = create_default(id);
+        piece.id = id.to_string();
+        piece.outline = [(size, 0.0), (size, size), (0.0, size), (0.0, 0.0)]
+            .into_iter()
+            .map(|(x, y)| CurveSegment::Line {
+                end: Point2D { x, y },
+                edge: Default::default(),
+            })
+            .collect();
+        piece
+    }
+
+    fn edge(piece_id: &str, segment: usize) -> OutlineSection {
+        OutlineSection {
+            piece_id: piece_id.to_string(),
+            start: OutlinePosition { segment, t: 0.0 },
+            end: OutlinePosition { segment, t: 1.0 },
+            reversed: false,
+        }
+    }
+
+    #[test]
+    fn notches_divide_both_sections_evenly() {
+        let (a, b) = (square("a", 110.0), square("b", 100.0));
+        let m = match_sections(&a, &edge("a", 0), &b, &edge("b", 0), 1).unwrap();
+        assert!((m.difference - 10.0).abs() < 1e-9);
+        assert!((m.ease_percent - 10.0).abs() < 1e-9);
+        assert_eq!(m.notches.len(), 1);
+        assert!((m.notches[0].a.point.x - 55.0).abs() < 1e-6);
+        assert!((m.notches[0].b.point.x - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn notch_count_is_bounded() {
+        let a = square("a", 100.0);
+        let result = match_sections(&a, &edge("a", 0), &a, &edge("a", 2), u32::MAX);
+        assert!(matches!(result, Err(AppError::InvalidInput { .. })));
+        let result = match_sections(&a, &edge("a", 0), &a, &edge("a", 2), MAX_MATCH_NOTCHES);
+        assert_eq!(result.unwrap().notches.len(), MAX_MATCH_NOTCHES as usize);
+    }
+}
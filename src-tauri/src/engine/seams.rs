@@ -0,0 +1,294 @@
+use uuid::Uuid;
+
+use crate::types::diagnostic::Diagnostic;
+use crate::types::error::{AppError, AppResult};
+use crate::types::event::ChangeEvent;
+use crate::types::pattern::OutlinePosition;
+use crate::types::seam::{Seam, SeamId};
+
+use super::document::Project;
+use super::history::{self, Delta};
+use super::{pattern_piece, seam_match, validation};
+
+/// Put one seam back to a recorded state, removing it for `None` (for undo/redo).
+pub fn restore_entry(project: &mut Project, id: &SeamId, seam: Option<Seam>) {
+    let previous = match seam {
+        Some(seam) => project.seams.insert(id.clone(), seam),
+        None => project.seams.remove(id),
+    };
+    let id = id.clone();
+    match (previous.is_some(), project.seams.contains_key(&id)) {
+        (false, true) => project.notify(ChangeEvent::SeamCreated { id }),
+        (true, true) => project.notify(ChangeEvent::SeamUpdated { id }),
+        (true, false) => project.notify(ChangeEvent::SeamDeleted { id }),
+        (false, false) => {}
+    }
+}
+
+/// Replace all seams in the project (used by project load).
+pub fn replace_all(project: &mut Project, seams: Vec<Seam>) {
+    project.seams = seams
+        .into_iter()
+        .map(|seam| (seam.id.clone(), seam))
+        .collect();
+}
+
+pub fn create(project: &mut Project, mut seam: Seam) -> AppResult<SeamId> {
+    check(project, &seam)?;
+    let id = Uuid::new_v4().to_string();
+    seam.id = id.clone();
+
+    let delta = Delta::default().seam(project, &id);
+    history::record(project, "Add seam", delta);
+
+    project.seams.insert(id.clone(), seam);
+    project.notify(ChangeEvent::SeamCreated { id: id.clone() });
+    Ok(id)
+}
+
+pub fn update(project: &mut Project, id: SeamId, mut seam: Seam) -> AppResult<()> {
+    check(project, &seam)?;
+    get(project, &id)?;
+
+    let delta = Delta::default().seam(project, &id);
+    history::record(project, "Edit seam", delta);
+
+    seam.id = id.clone();
+    project.seams.insert(id.clone(), seam);
+    project.notify(ChangeEvent::SeamUpdated { id });
+    Ok(())
+}
+
+pub fn delete(project: &mut Project, id: &SeamId) -> AppResult<()> {
+    get(project, id)?;
+    let delta = Delta::default().seam(project, id);
+    history::record(project, "Delete seam", delta);
+
+    project.seams.remove(id);
+    project.notify(ChangeEvent::SeamDeleted { id: id.clone() });
+    Ok(())
+}
+
+pub fn get(project: &Project, id: &SeamId) -> AppResult<Seam> {
+    project
+        .seams
+        .get(id)
+        .cloned()
+        .ok_or_else(|| AppError::not_found(format!("Seam not found: {id}")).with_seam(id))
+}
+
+pub fn get_all(project: &Project) -> Vec<Seam> {
+    project.seams.values().cloned().collect()
+}
+
+/// Ids of the seams attached to a piece.
+pub fn ids_for_piece(project: &Project, piece_id: &str) -> Vec<SeamId> {
+    project
+        .seams
+        .values()
+        .filter(|seam| seam.a.piece_id == piece_id || seam.b.piece_id == piece_id)
+        .map(|seam| seam.id.clone())
+        .collect()
+}
+
+/// The ends of every section on a piece, in the order [`move_anchors`] takes them back, for
+/// carrying through an edit that rebuilds the piece's outline.
+pub fn anchors_on_piece(project: &Project, piece_id: &str) -> Vec<OutlinePosition> {
+    let mut anchors = Vec::new();
+    for id in sorted_ids_for_piece(project, piece_id) {
+        let seam = &project.seams[&id];
+        for section in [&seam.a, &seam.b] {
+            if section.piece_id == piece_id {
+                anchors.extend([section.start, section.end]);
+            }
+        }
+    }
+    anchors
+}
+
+/// Move the sections on a piece to `anchors`, the positions from [`anchors_on_piece`] carried
+/// through an edit of its outline. When the edit `reversed` the outline, each section's ends
+/// swap and it is walked the other way, so it covers the same stretch in the same direction.
+/// Called inside the undo step of the edit.
+pub fn move_anchors(
+    project: &mut Project,
+    piece_id: &str,
+    anchors: &[OutlinePosition],
+    reversed: bool,
+) {
+    let mut anchors = anchors.chunks_exact(2);
+    for id in sorted_ids_for_piece(project, piece_id) {
+        let Some(seam) = project.seams.get_mut(&id) else {
+            continue;
+        };
+        for section in [&mut seam.a, &mut seam.b] {
+            if section.piece_id != piece_id {
+                continue;
+            }
+            let Some(&[start, end]) = anchors.next() else {
+                return;
+            };
+            if reversed {
+                (section.start, section.end) = (end, start);
+                section.reversed = !section.reversed;
+            } else {
+                (section.start, section.end) = (start, end);
+            }
+        }
+        project.notify(ChangeEvent::SeamUpdated { id });
+    }
+}
+
+/// `InvalidSeam` errors for the seams on a piece whose sections no longer fit its outline.
+pub fn check_piece(project: &Project, piece_id: &str) -> Vec<Diagnostic> {
+    sorted_ids_for_piece(project, piece_id)
+        .into_iter()
+        .filter_map(|id| {
+            let e = check(project, &project.seams[&id]).err()?;
+            Some(validation::invalid_seam(&id, &e))
+        })
+        .collect()
+}
+
+fn sorted_ids_for_piece(project: &Project, piece_id: &str) -> Vec<SeamId> {
+    let mut ids = ids_for_piece(project, piece_id);
+    ids.sort();
+    ids
+}
+
+/// Drop every seam attached to a piece. Called when the piece is deleted, inside the
+/// same undo step.
+pub fn remove_for_piece(project: &mut Project, piece_id: &str) {
+    for id in ids_for_piece(project, piece_id) {
+        project.seams.remove(&id);
+        project.notify(ChangeEvent::SeamDeleted { id });
+    }
+}
+
+/// Both sections must lie on existing pieces; length mismatches are left to validation.
+fn check(project: &Project, seam: &Seam) -> AppResult<()> {
+    if !seam.ease_mm.is_finite() {
+        return Err(AppError::invalid_input(format!(
+            "Invalid seam ease: {}",
+            seam.ease_mm
+        )));
+    }
+    for section in [&seam.a, &seam.b] {
+        let piece = pattern_piece::get(project, &section.piece_id)?;
+        seam_match::section_length(&piece, section)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{history, repair, transform};
+    use crate::geometry::transform::Transform2D;
+    use crate::types::pattern::{CurveSegment, PatternPieceData, PatternPieceId, Point2D};
+    use crate::types::seam::{OutlineSection, SeamType};
+
+    fn piece(corners: &[(f64, f64)]) -> PatternPieceData {
+        let mut piece = pattern_piece::create_default("Piece");
+        piece.outline = corners
+            .iter()
+            .map(|&(x, y)| CurveSegment::Line {
+                end: Point2D { x, y },
+                edge: Default::default(),
+            })
+            .collect();
+        piece
+    }
+
+    fn section(piece_id: &str, start: (usize, f64), end: (usize, f64)) -> OutlineSection {
+        OutlineSection {
+            piece_id: piece_id.to_string(),
+            start: OutlinePosition {
+                segment: start.0,
+                t: start.1,
+            },
+            end: OutlinePosition {
+                segment: end.0,
+                t: end.1,
+            },
+            reversed: false,
+        }
+    }
+
+    /// A clockwise square whose bottom edge is split at (50, 0), seamed along its right half
+    /// to another square.
+    fn seamed_project() -> (Project, PatternPieceId, SeamId) {
+        let mut project = Project::default();
+        let square = [(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)];
+        let other = pattern_piece::create(&mut project, piece(&square)).unwrap();
+        let split = [
+            (0.0, 100.0),
+            (100.0, 100.0),
+            (100.0, 0.0),
+            (50.0, 0.0),
+            (0.0, 0.0),
+        ];
+        let id = pattern_piece::create(&mut project, piece(&split)).unwrap();
+        let seam = Seam {
+            id: String::new(),
+            a: section(&id, (3, 0.0), (3, 1.0)),
+            b: section(&other, (0, 0.0), (0, 0.5)),
+            seam_type: SeamType::Plain,
+            ease_mm: 0.0,
+        };
+        let seam_id = create(&mut project, seam).unwrap();
+        (project, id, seam_id)
+    }
+
+    #[test]
+    fn repair_carries_seam_ends_with_the_outline() {
+        let (mut project, id, seam_id) = seamed_project();
+        repair::apply_repair(&mut project, &id, 0.1).unwrap();
+
+        // Merging the bottom edge halves, then reversing the outline, leaves the seam on the
+        // second half of segment 0, walked backwards from (100, 0) as before.
+        let a = &project.seams[&seam_id].a;
+        assert_eq!((a.start.segment, a.start.t), (0, 0.5));
+        assert_eq!((a.end.segment, a.end.t), (0, 1.0));
+        assert!(a.reversed);
+        assert!(check_piece(&project, &id).is_empty());
+
+        history::undo(&mut project).unwrap();
+        let a = &project.seams[&seam_id].a;
+        assert_eq!((a.start.segment, a.end.segment), (3, 3));
+        assert!(!a.reversed);
+        assert_eq!(project.pieces[&id].outline.len(), 5);
+    }
+
+    #[test]
+    fn mirroring_carries_seam_ends_with_the_outline() {
+        let (mut project, id, seam_id) = seamed_project();
+        transform::apply_transform(&mut project, &id, &Transform2D::mirror_x()).unwrap();
+
+        // The mirrored outline runs the other way, so the bottom edge half that was segment
+        // 3 of 5 is now segment 1, walked backwards.
+        let a = &project.seams[&seam_id].a;
+        assert_eq!((a.start.segment, a.start.t), (1, 0.0));
+        assert_eq!((a.end.segment, a.end.t), (1, 1.0));
+        assert!(a.reversed);
+        assert!(check_piece(&project, &id).is_empty());
+
+        // One undo step puts the piece and the seam back together.
+        history::undo(&mut project).unwrap();
+        let a = &project.seams[&seam_id].a;
+        assert_eq!((a.start.segment, a.end.segment), (3, 3));
+        assert!(!a.reversed);
+    }
+
+    #[test]
+    fn seams_left_off_the_outline_are_reported() {
+        let (mut project, id, seam_id) = seamed_project();
+        let mut shrunk = project.pieces[&id].clone();
+        shrunk.outline.truncate(2);
+        pattern_piece::update(&mut project, id.clone(), shrunk).unwrap();
+
+        let diagnostics = check_piece(&project, &id);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].seam_id.as_ref(), Some(&seam_id));
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::types::error::{AppError, AppResult};
+
+/// Write `data` to `path` without ever leaving a partly written file there. The data goes
+/// to a temporary file in the same directory, is flushed to disk, then renamed over `path`.
+pub fn write_atomic(path: &Path, data: &[u8]) -> AppResult<()> {
+    let dir = path
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::invalid_input("Invalid file path").with_path(path))?;
+    let temp = dir.join(format!(".{name}.{}.tmp", Uuid::new_v4()));
+
+    let result = write_synced(&temp, data)
+        .and_then(|()| fs::rename(&temp, path).map_err(|e| write_error(e, path)));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result?;
+    sync_dir(dir);
+    Ok(())
+}
+
+fn write_synced(path: &Path, data: &[u8]) -> AppResult<()> {
+    let mut file = File::create(path).map_err(|e| write_error(e, path))?;
+    file.write_all(data).map_err(|e| write_error(e, path))?;
+    file.sync_all().map_err(|e| write_error(e, path))
+}
+
+fn write_error(e: io::Error, path: &Path) -> AppError {
+    AppError::io(format!("Write error: {e}")).with_path(path)
+}
+
+/// Describe a failure to read `path`, as `NotFound` if it doesn't exist.
+pub fn read_error(e: io::Error, path: &Path) -> AppError {
+    let error = if e.kind() == io::ErrorKind::NotFound {
+        AppError::not_found(format!("File not found: {}", path.display()))
+    } else {
+        AppError::io(format!("Read error: {e}"))
+    };
+    error.with_path(path)
+}
+
+/// Flush a directory so a rename inside it survives a power cut.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Windows can't open directories as files; renames there are flushed with the file.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+/// The `n`th backup of `path`: `<path>.1.bak` is the most recent.
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}.bak"));
+    PathBuf::from(name)
+}
+
+/// Copy the current contents of `path`, if any, into its first backup slot, shifting older
+/// backups along and dropping any beyond `count`.
+pub fn rotate_backups(path: &Path, count: usize) -> AppResult<()> {
+    if count == 0 || !path.exists() {
+        return Ok(());
+    }
+    let _ = fs::remove_file(backup_path(path, count));
+    for n in (1..count).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, n + 1))
+                .map_err(|e| AppError::io(format!("Backup error: {e}")).with_path(&from))?;
+        }
+    }
+    let current =
+        fs::read(path).map_err(|e| AppError::io(format!("Backup error: {e}")).with_path(path))?;
+    write_atomic(&backup_path(path, 1), &current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::project::DEFAULT_BACKUP_COUNT;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tcad-storage-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn write_replaces_the_file_and_leaves_no_temp_file() {
+        let dir = temp_dir();
+        let path = dir.join("shirt.tcad");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(files(&dir), ["shirt.tcad"]);
+
+        // A failed write cleans up after itself too.
+        assert!(write_atomic(&dir.join("missing").join("shirt.tcad"), b"x").is_err());
+        assert_eq!(files(&dir), ["shirt.tcad"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn backups_rotate_up_to_the_count() {
+        let dir = temp_dir();
+        let path = dir.join("shirt.tcad");
+        // Nothing to back up before the first save.
+        rotate_backups(&path, DEFAULT_BACKUP_COUNT).unwrap();
+        assert!(files(&dir).is_empty());
+
+        let saves = DEFAULT_BACKUP_COUNT + 2;
+        for n in 0..saves {
+            rotate_backups(&path, DEFAULT_BACKUP_COUNT).unwrap();
+            write_atomic(&path, format!("save {n}").as_bytes()).unwrap();
+        }
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), format!("save {}", saves - 1));
+        for n in 1..=DEFAULT_BACKUP_COUNT {
+            assert_eq!(
+                read(backup_path(&path, n)),
+                format!("save {}", saves - 1 - n)
+            );
+        }
+        assert!(!backup_path(&path, DEFAULT_BACKUP_COUNT + 1).exists());
+        assert_eq!(files(&dir).len(), DEFAULT_BACKUP_COUNT + 1);
+
+        // A count of zero makes no new backup.
+        rotate_backups(&path, 0).unwrap();
+        assert_eq!(files(&dir).len(), DEFAULT_BACKUP_COUNT + 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
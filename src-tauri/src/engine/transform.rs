@@ -0,0 +1,404 @@
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::transform::Transform2D;
+use crate::geometry::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{
+    CurveSegment, CutOut, EdgeAttributes, Marking, MarkingKind, OutlinePosition, PatternPieceData,
+    PatternPieceId, Point2D,
+};
+
+use super::document::Project;
+use super::repair::mirror_corner;
+use super::validation::CLOSE_TOLERANCE;
+use super::{pattern_piece, seams};
+
+/// Outline curves after transforming, with the span each original segment now covers.
+struct Mapped {
+    curves: Vec<Curve>,
+    edges: Vec<EdgeAttributes>,
+    /// First new index and number of curves for each original curve.
+    spans: Vec<(usize, usize)>,
+}
+
+impl Mapped {
+    /// New position of the point at parameter `t` on original segment `segment`.
+    /// Indices past the original curves (the implicit closing edge) shift along with them.
+    fn position(&self, segment: usize, t: f64) -> (usize, f64) {
+        match self.spans.get(segment) {
+            Some(&(first, count)) => {
+                let scaled = t.clamp(0.0, 1.0) * count as f64;
+                let piece = (scaled.floor() as usize).min(count - 1);
+                (first + piece, scaled - piece as f64)
+            }
+            None => (segment - self.spans.len() + self.curves.len(), t),
+        }
+    }
+
+    /// Move an outline position to the transformed outline, before any reversal.
+    fn map(&self, position: &mut OutlinePosition) {
+        (position.segment, position.t) = self.position(position.segment, position.t);
+    }
+}
+
+/// Where a position on an outline of `n` segments lies once the outline is reversed.
+fn reverse_position(position: &mut OutlinePosition, n: usize) {
+    position.segment = n - 1 - position.segment.min(n - 1);
+    position.t = 1.0 - position.t;
+}
+
+/// Apply an affine transform to every part of a piece: outline, cut-outs, internal lines,
+/// notches, markings and grain line. Arcs that the transform distorts become Béziers, and a
+/// mirroring transform also reverses the outline so it keeps the editor's winding.
+/// Seam allowances and the sizes of drill holes and buttons are not scaled.
+///
+/// `anchors` are other positions on the outline, such as the ends of seams. They are returned
+/// moved to the same places on the transformed outline, along with the transformed copy.
+pub fn transform_piece(
+    piece: &PatternPieceData,
+    transform: &Transform2D,
+    mut anchors: Vec<OutlinePosition>,
+) -> AppResult<(PatternPieceData, Vec<OutlinePosition>)> {
+    let finite = [
+        transform.a,
+        transform.b,
+        transform.c,
+        transform.d,
+        transform.tx,
+        transform.ty,
+    ]
+    .iter()
+    .all(|v| v.is_finite());
+    if !finite || transform.inverse().is_none() {
+        return Err(AppError::invalid_input(
+            "Transform must be finite and invertible",
+        ));
+    }
+    let reflects = transform.is_reflection();
+
+    // Reversing the outline makes its implicit closing edge explicit.
+    let curves = if reflects {
+        curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE)
+    } else {
+        curve::resolve(&piece.origin, &piece.outline)
+    };
+    let edges: Vec<EdgeAttributes> = (0..curves.len())
+        .map(|i| {
+            piece
+                .outline
+                .get(i)
+                .map(|s| s.edge().clone())
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut mapped = map_curves(&curves, &edges, transform);
+
+    let mut result = piece.clone();
+    result.origin = map_point(transform, &piece.origin);
+    result.notches = piece
+        .notches
+        .iter()
+        .map(|notch| {
+            let mut notch = notch.clone();
+            mapped.map(&mut notch.position);
+            notch
+        })
+        .collect();
+    anchors.iter_mut().for_each(|a| mapped.map(a));
+    result.fold_edge = piece.fold_edge.map(|edge| mapped.position(edge, 0.5).0);
+
+    if reflects && !mapped.curves.is_empty() {
+        reverse(&mut mapped);
+        let n = mapped.curves.len();
+        for notch in &mut result.notches {
+            reverse_position(&mut notch.position, n);
+            // The side a notch leans to flips with the mirror image.
+            notch.angle = -notch.angle;
+        }
+        anchors.iter_mut().for_each(|a| reverse_position(a, n));
+        result.fold_edge = result.fold_edge.map(|k| n - 1 - k.min(n - 1));
+    }
+    result.outline = to_segments(mapped.curves, mapped.edges);
+
+    result.cut_outs = piece
+        .cut_outs
+        .iter()
+        .map(|c| CutOut {
+            origin: map_point(transform, &c.origin),
+            outline: map_path(&c.origin, &c.outline, transform),
+        })
+        .collect();
+    result.internal_lines = piece
+        .internal_lines
+        .iter()
+        .map(|line| map_path(&piece.origin, line, transform))
+        .collect();
+    result.markings = piece
+        .markings
+        .iter()
+        .map(|m| map_marking(m, transform))
+        .collect();
+    result.grain_line = piece
+        .grain_line
+        .as_ref()
+        .map(|(s, e)| (map_point(transform, s), map_point(transform, e)));
+
+    Ok((result, anchors))
+}
+
+/// Transform a stored piece as one undo step, moving the ends of its seams along with the
+/// outline. Returns the transformed piece.
+pub fn apply_transform(
+    project: &mut Project,
+    id: &PatternPieceId,
+    transform: &Transform2D,
+) -> AppResult<PatternPieceData> {
+    let piece = pattern_piece::get(project, id)?;
+    let anchors = seams::anchors_on_piece(project, id);
+    let (transformed, anchors) = transform_piece(&piece, transform, anchors)?;
+    pattern_piece::update_reworked(
+        project,
+        id.clone(),
+        transformed.clone(),
+        format!("Transform {}", piece.name),
+        &anchors,
+        transform.is_reflection(),
+    )?;
+    Ok(transformed)
+}
+
+/// Add a transformed copy of a stored piece named `name`, returning the new piece's id.
+pub fn duplicate_piece(
+    project: &mut Project,
+    id: &PatternPieceId,
+    name: String,
+    transform: &Transform2D,
+) -> AppResult<PatternPieceId> {
+    let piece = pattern_piece::get(project, id)?;
+    let (mut copy, _) = transform_piece(&piece, transform, Vec::new())?;
+    copy.name = name;
+    pattern_piece::create(project, copy)
+}
+
+fn map_point(transform: &Transform2D, p: &Point2D) -> Point2D {
+    transform.apply(&Vec2::from(p)).into()
+}
+
+/// Transform curves, giving every curve an arc was split into the arc's seam allowance and
+/// leaving its corner on the last one, where the original vertex is.
+fn map_curves(curves: &[Curve], edges: &[EdgeAttributes], transform: &Transform2D) -> Mapped {
+    let mut mapped = Mapped {
+        curves: Vec::with_capacity(curves.len()),
+        edges: Vec::with_capacity(curves.len()),
+        spans: Vec::with_capacity(curves.len()),
+    };
+    for (c, edge) in curves.iter().zip(edges) {
+        let pieces = c.transformed(transform);
+        let count = pieces.len();
+        mapped.spans.push((mapped.curves.len(), count));
+        for (i, piece) in pieces.into_iter().enumerate() {
+            mapped.curves.push(piece);
+            mapped.edges.push(EdgeAttributes {
+                seam_allowance_mm: edge.seam_allowance_mm,
+                corner: if i + 1 == count { edge.corner } else { None },
+            });
+        }
+    }
+    mapped
+}
+
+/// Traverse the outline the other way. Corners live on the segment ending at their vertex, so
+/// the reversed segment k takes the corner that used to end segment k - 1.
+fn reverse(mapped: &mut Mapped) {
+    let n = mapped.curves.len();
+    let edges = (0..n)
+        .rev()
+        .map(|k| EdgeAttributes {
+            seam_allowance_mm: mapped.edges[k].seam_allowance_mm,
+            corner: mapped.edges[(k + n - 1) % n].corner.map(mirror_corner),
+        })
+        .collect();
+    mapped.curves = mapped.curves.iter().rev().map(Curve::reversed).collect();
+    mapped.edges = edges;
+}
+
+fn to_segments(curves: Vec<Curve>, edges: Vec<EdgeAttributes>) -> Vec<CurveSegment> {
+    curves
+        .into_iter()
+        .zip(edges)
+        .map(|(c, edge)| c.to_segment(edge))
+        .collect()
+}
+
+fn map_path(
+    origin: &Point2D,
+    segments: &[CurveSegment],
+    transform: &Transform2D,
+) -> Vec<CurveSegment> {
+    let curves = curve::resolve(origin, segments);
+    let edges: Vec<EdgeAttributes> = segments.iter().map(|s| s.edge().clone()).collect();
+    let mapped = map_curves(&curves, &edges, transform);
+    to_segments(mapped.curves, mapped.edges)
+}
+
+/// Drill holes and buttons keep their size. Directed markings follow their far end, and a
+/// mirrored one runs back from it so a "place on fold" bracket stays on the mirrored side.
+fn map_marking(marking: &Marking, transform: &Transform2D) -> Marking {
+    let position = Vec2::from(&marking.position);
+    match marking.kind {
+        MarkingKind::DrillHole | MarkingKind::Button => Marking {
+            position: transform.apply(&position).into(),
+            ..marking.clone()
+        },
+        _ => {
+            let dir = Vec2::new(marking.angle.cos(), marking.angle.sin()) * marking.size_mm;
+            let (start, end) = (
+                transform.apply(&position),
+                transform.apply(&(position + dir)),
+            );
+            let (start, end) = if transform.is_reflection() {
+                (end, start)
+            } else {
+                (start, end)
+            };
+            Marking {
+                position: start.into(),
+                size_mm: start.distance(&end),
+                angle: (end - start).angle(),
+                ..marking.clone()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::engine::pattern_piece::create_default;
+    use crate::engine::validation::compute_area;
+    use crate::types::pattern::{Notch, NotchKind};
+
+    fn p(x: f64, y: f64) -> Point2D {
+        Point2D { x, y }
+    }
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: p(x, y),
+            edge: Default::default(),
+        }
+    }
+
+    fn notch(segment: usize, t: f64, angle: f64) -> Notch {
+        Notch {
+            position: OutlinePosition { segment, t },
+            kind: NotchKind::Slit,
+            depth_mm: 6.0,
+            width_mm: 4.0,
+            angle,
+        }
+    }
+
+    /// A 100 mm square with a half-circle bulging from its right side.
+    fn rounded() -> PatternPieceData {
+        let mut piece = create_default("Sleeve");
+        piece.outline = vec![
+            line(100.0, 0.0),
+            CurveSegment::Arc {
+                center: p(100.0, 50.0),
+                radius: 50.0,
+                start_angle: -FRAC_PI_2,
+                end_angle: FRAC_PI_2,
+                edge: Default::default(),
+            },
+            line(0.0, 100.0),
+        ];
+        piece.notches = vec![notch(1, 0.5, 0.0)];
+        piece
+    }
+
+    /// Where an outline position lies on a piece.
+    fn point_at(piece: &PatternPieceData, position: &OutlinePosition) -> Vec2 {
+        let curves = curve::resolve_closed(&piece.origin, &piece.outline, CLOSE_TOLERANCE);
+        curves[position.segment].point_at(position.t)
+    }
+
+    #[test]
+    fn uniform_scale_keeps_arcs() {
+        let piece = rounded();
+        let (scaled, _) = transform_piece(&piece, &Transform2D::scale(2.0, 2.0), vec![]).unwrap();
+        assert_eq!(scaled.outline.len(), 3);
+        assert!(matches!(
+            scaled.outline[1],
+            CurveSegment::Arc { radius, .. } if (radius - 100.0).abs() < 1e-9
+        ));
+        let area = compute_area(&piece).unwrap();
+        assert!((compute_area(&scaled).unwrap() - 4.0 * area).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stretched_arcs_become_cubics() {
+        let piece = rounded();
+        let stretch = Transform2D::scale(2.0, 1.0);
+        let (stretched, _) = transform_piece(&piece, &stretch, vec![]).unwrap();
+
+        // The half circle is split at its apex into two quarter ellipses.
+        assert_eq!(stretched.outline.len(), 4);
+        for (segment, end) in [(1, (300.0, 50.0)), (2, (200.0, 100.0))] {
+            match &stretched.outline[segment] {
+                CurveSegment::CubicBezier { end: e, .. } => assert_eq!((e.x, e.y), end),
+                other => panic!("segment {segment} is {other:?}"),
+            }
+        }
+        let area = compute_area(&stretched).unwrap();
+        let expected = 2.0 * compute_area(&piece).unwrap();
+        assert!(
+            (area - expected).abs() < expected * 1e-3,
+            "{area} vs {expected}"
+        );
+
+        // The notch at the middle of the arc now starts the second quarter.
+        let position = &stretched.notches[0].position;
+        assert_eq!((position.segment, position.t), (2, 0.0));
+        let mapped = stretch.apply(&point_at(&piece, &piece.notches[0].position));
+        assert!(point_at(&stretched, position).distance(&mapped) < 1e-9);
+    }
+
+    #[test]
+    fn mirroring_reverses_the_outline() {
+        let mut piece = create_default("Front");
+        piece.outline = vec![line(100.0, 0.0), line(100.0, 100.0), line(0.0, 100.0)];
+        piece.notches = vec![notch(0, 0.25, 0.2), notch(3, 0.5, 0.0)];
+        piece.fold_edge = Some(1);
+        let mirror = Transform2D::mirror_x();
+        let (mirrored, _) = transform_piece(&piece, &mirror, vec![]).unwrap();
+
+        // The closing edge becomes explicit and the outline still runs counter-clockwise.
+        assert_eq!(mirrored.outline.len(), 4);
+        let curves = curve::resolve_closed(&mirrored.origin, &mirrored.outline, CLOSE_TOLERANCE);
+        assert!(curve::closed_signed_area(&curves) > 0.0);
+
+        // Notches stay on the mirror image of their place, leaning the other way.
+        for (before, after) in piece.notches.iter().zip(&mirrored.notches) {
+            let mapped = mirror.apply(&point_at(&piece, &before.position));
+            assert!(point_at(&mirrored, &after.position).distance(&mapped) < 1e-9);
+            assert_eq!(after.angle, -before.angle);
+        }
+        assert_eq!(
+            (
+                mirrored.notches[0].position.segment,
+                mirrored.notches[0].position.t
+            ),
+            (3, 0.75)
+        );
+
+        // The fold edge is the mirrored right side, now run downwards as segment 2.
+        assert_eq!(mirrored.fold_edge, Some(2));
+        let fold = &curves[2];
+        assert_eq!(
+            (fold.start(), fold.end()),
+            (Vec2::new(-100.0, 100.0), Vec2::new(-100.0, 0.0))
+        );
+    }
+}
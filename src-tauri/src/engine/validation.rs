@@ -1,31 +1,470 @@
-use crate::types::pattern::PatternPieceData;
+use std::collections::HashMap;
+
+use crate::geometry::curve::{self, Curve};
+use crate::geometry::intersection;
+use crate::geometry::vec2::Vec2;
+use crate::types::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::{CurveSegment, PatternPieceData, PatternPieceId, Point2D};
+use crate::types::seam::{Seam, SeamId};
+
+use super::{fold, seam_match};
+
+/// Endpoints closer than this (mm) are considered coincident.
+pub const CLOSE_TOLERANCE: f64 = 0.01;
+/// Segments shorter than this (mm) are considered collapsed.
+pub const DEGENERATE_LENGTH: f64 = 0.001;
+/// Flattening tolerance (mm) used when searching for crossings between curves.
+const FLATTEN_TOLERANCE: f64 = 0.01;
 
 /// Validate geometry of a pattern piece.
-/// Returns a list of warnings/errors (empty = valid).
-pub fn validate(piece: &PatternPieceData) -> Vec<String> {
-    let mut warnings = Vec::new();
+/// Returns a list of diagnostics (empty = valid).
+pub fn validate(piece: &PatternPieceData) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
 
     if piece.outline.is_empty() {
-        warnings.push("Piece has no outline segments".to_string());
+        diagnostics.push(Diagnostic::error(
+            DiagnosticCode::EmptyOutline,
+            "Piece has no outline segments",
+        ));
     }
 
     if piece.name.is_empty() {
-        warnings.push("Piece has no name".to_string());
+        diagnostics.push(Diagnostic::warning(
+            DiagnosticCode::MissingName,
+            "Piece has no name",
+        ));
     }
 
     if piece.seam_allowance_mm < 0.0 {
-        warnings.push("Seam allowance cannot be negative".to_string());
+        diagnostics.push(Diagnostic::error(
+            DiagnosticCode::NegativeSeamAllowance,
+            "Seam allowance cannot be negative",
+        ));
+    }
+
+    for (i, seg) in piece.outline.iter().enumerate() {
+        if seg.edge().seam_allowance_mm.is_some_and(|mm| mm < 0.0) {
+            diagnostics.push(
+                Diagnostic::error(
+                    DiagnosticCode::NegativeSeamAllowance,
+                    format!("Seam allowance on segment {i} cannot be negative"),
+                )
+                .at_segment(i),
+            );
+        }
+    }
+
+    if piece.outline.is_empty() {
+        return diagnostics;
+    }
+    if !all_finite(piece) {
+        diagnostics.push(Diagnostic::error(
+            DiagnosticCode::NonFiniteCoordinate,
+            "Outline contains non-finite coordinates",
+        ));
+        return diagnostics;
+    }
+
+    let curves = curve::resolve(&piece.origin, &piece.outline);
+    check_continuity(piece, &curves, &mut diagnostics);
+    check_degenerate(&curves, &mut diagnostics);
+    check_self_intersections(piece, &curves, &mut diagnostics);
+    check_fold_edge(piece, &mut diagnostics);
+
+    diagnostics
+}
+
+fn check_fold_edge(piece: &PatternPieceData, out: &mut Vec<Diagnostic>) {
+    let Err(message) = fold::fold_line(piece) else {
+        return;
+    };
+    let mut diagnostic = Diagnostic::error(DiagnosticCode::InvalidFoldEdge, message.to_string());
+    if let Some(i) = piece.fold_edge.filter(|&i| i < piece.outline.len()) {
+        diagnostic = diagnostic.at_segment(i);
+    }
+    out.push(diagnostic);
+}
+
+fn all_finite(piece: &PatternPieceData) -> bool {
+    let finite = |p: &Point2D| p.x.is_finite() && p.y.is_finite();
+    finite(&piece.origin)
+        && piece.outline.iter().all(|seg| match seg {
+            CurveSegment::Line { end, .. } => finite(end),
+            CurveSegment::QuadraticBezier { control, end, .. } => finite(control) && finite(end),
+            CurveSegment::CubicBezier {
+                control1,
+                control2,
+                end,
+                ..
+            } => finite(control1) && finite(control2) && finite(end),
+            CurveSegment::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => {
+                finite(center)
+                    && radius.is_finite()
+                    && start_angle.is_finite()
+                    && end_angle.is_finite()
+            }
+        })
+}
+
+/// Every segment must start where the previous one ended (arcs carry their own start),
+/// and the last segment must return to the origin.
+fn check_continuity(piece: &PatternPieceData, curves: &[Curve], out: &mut Vec<Diagnostic>) {
+    let origin = Vec2::from(&piece.origin);
+    let mut current = origin;
+    for (i, c) in curves.iter().enumerate() {
+        let start = c.start();
+        if start.distance(&current) > CLOSE_TOLERANCE {
+            out.push(
+                Diagnostic::error(
+                    DiagnosticCode::DisconnectedSegment,
+                    format!(
+                        "Segment {i} starts {:.2} mm away from the end of the previous segment",
+                        start.distance(&current)
+                    ),
+                )
+                .at_segment(i)
+                .at_point(start.into()),
+            );
+        }
+        current = c.end();
     }
 
-    // TODO: check for self-intersections
-    // TODO: check that outline forms a closed path
-    // TODO: check for degenerate segments (zero-length)
+    let gap = current.distance(&origin);
+    if gap > CLOSE_TOLERANCE {
+        out.push(
+            Diagnostic::error(
+                DiagnosticCode::OpenOutline,
+                format!("Outline does not return to its origin (gap of {gap:.2} mm)"),
+            )
+            .at_segment(curves.len() - 1)
+            .at_point(current.into()),
+        );
+    }
+}
+
+fn check_degenerate(curves: &[Curve], out: &mut Vec<Diagnostic>) {
+    for (i, c) in curves.iter().enumerate() {
+        if let Curve::Arc { radius, .. } = c {
+            if *radius <= 0.0 {
+                out.push(
+                    Diagnostic::error(
+                        DiagnosticCode::DegenerateSegment,
+                        format!("Arc segment {i} has a non-positive radius"),
+                    )
+                    .at_segment(i)
+                    .at_point(c.start().into()),
+                );
+                continue;
+            }
+        }
+        if c.flattened_length(FLATTEN_TOLERANCE) < DEGENERATE_LENGTH {
+            out.push(
+                Diagnostic::warning(
+                    DiagnosticCode::DegenerateSegment,
+                    format!("Segment {i} has zero length"),
+                )
+                .at_segment(i)
+                .at_point(c.start().into()),
+            );
+        }
+    }
+}
+
+/// Flatten every segment (curves included) into one closed polygon, remembering which
+/// segment each polygon edge came from, and report each crossing pair of segments once.
+fn check_self_intersections(piece: &PatternPieceData, curves: &[Curve], out: &mut Vec<Diagnostic>) {
+    let origin = Vec2::from(&piece.origin);
+    let mut points = vec![origin];
+    // Outline segment of the polygon edge ending at points[k + 1]; `None` is the implicit
+    // closing edge of an open outline.
+    let mut edge_segments: Vec<Option<usize>> = Vec::new();
+    for (i, c) in curves.iter().enumerate() {
+        for p in c.flatten(FLATTEN_TOLERANCE) {
+            if points.last().is_some_and(|last| last.distance(&p) > 1e-9) {
+                points.push(p);
+                edge_segments.push(Some(i));
+            }
+        }
+    }
+    if points
+        .last()
+        .is_some_and(|last| last.distance(&origin) <= 1e-9)
+    {
+        points.pop();
+    } else {
+        edge_segments.push(None);
+    }
+    if points.len() < 3 {
+        return;
+    }
+
+    let describe = |seg: Option<usize>| match seg {
+        Some(i) => format!("segment {i}"),
+        None => "the closing edge".to_string(),
+    };
+
+    let mut reported: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+    for (i, j, t, _) in intersection::polygon_self_intersections(&points) {
+        let (a, b) = (edge_segments[i], edge_segments[j]);
+        let pair = if a <= b { (a, b) } else { (b, a) };
+        if reported.contains(&pair) {
+            continue;
+        }
+        reported.push(pair);
+
+        let p0 = points[i];
+        let p1 = points[(i + 1) % points.len()];
+        let message = if a == b {
+            format!("Outline {} crosses itself", describe(a))
+        } else {
+            format!("Outline {} crosses {}", describe(pair.0), describe(pair.1))
+        };
+        let mut diagnostic = Diagnostic::error(DiagnosticCode::SelfIntersection, message)
+            .at_point(p0.lerp(&p1, t).into());
+        if let Some(seg) = pair.0.or(pair.1) {
+            diagnostic = diagnostic.at_segment(seg);
+        }
+        out.push(diagnostic);
+    }
+}
+
+/// Validate seams against the pieces they join. A seam is flagged when the length difference
+/// of its two sections departs from its `ease_mm` by more than `tolerance_mm`.
+pub fn validate_seams(
+    seams: &[Seam],
+    pieces: &HashMap<PatternPieceId, PatternPieceData>,
+    tolerance_mm: f64,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for seam in seams {
+        let lengths = [&seam.a, &seam.b].map(|section| {
+            let piece = pieces.get(&section.piece_id).ok_or_else(|| {
+                AppError::not_found(format!("Piece not found: {}", section.piece_id))
+                    .with_piece(&section.piece_id)
+            })?;
+            seam_match::section_length(piece, section)
+        });
+        let [length_a, length_b] = match lengths {
+            [Ok(a), Ok(b)] => [a, b],
+            [Err(e), _] | [_, Err(e)] => {
+                diagnostics.push(invalid_seam(&seam.id, &e));
+                continue;
+            }
+        };
+
+        let mismatch = length_a - length_b - seam.ease_mm;
+        if mismatch.abs() > tolerance_mm {
+            diagnostics.push(
+                Diagnostic::warning(
+                    DiagnosticCode::SeamLengthMismatch,
+                    format!(
+                        "Seam sections measure {length_a:.1} mm and {length_b:.1} mm, \
+                         {mismatch:+.1} mm off the intended {:.1} mm ease",
+                        seam.ease_mm
+                    ),
+                )
+                .for_seam(&seam.id),
+            );
+        }
+    }
+
+    diagnostics
+}
 
-    warnings
+/// The error reported for a seam whose sections can't be measured.
+pub fn invalid_seam(id: &SeamId, error: &AppError) -> Diagnostic {
+    Diagnostic::error(
+        DiagnosticCode::InvalidSeam,
+        format!("Invalid seam: {error}"),
+    )
+    .for_seam(id)
 }
 
-/// Compute the area of a pattern piece outline using the shoelace formula.
-/// Only considers line segments for now — curves need to be sampled first.
-pub fn compute_area(_piece: &PatternPieceData) -> Result<f64, String> {
-    Err("Area computation not yet implemented".to_string())
+/// Compute the area (mm²) of a pattern piece, net of its cut-outs.
+/// Curved segments are integrated exactly rather than sampled. The result does not depend
+/// on the winding of the outline or of the cut-outs. Cut-on-fold pieces are measured unfolded.
+pub fn compute_area(piece: &PatternPieceData) -> AppResult<f64> {
+    if piece.outline.is_empty() {
+        return Err(
+            AppError::invalid_geometry("Piece has no outline segments").with_piece(&piece.id)
+        );
+    }
+    let piece = &fold::unfold(piece)?;
+
+    let outline_area = curve::closed_signed_area(&curve::resolve(&piece.origin, &piece.outline));
+    let cut_out_area: f64 = piece
+        .cut_outs
+        .iter()
+        .filter(|c| !c.outline.is_empty())
+        .map(|c| curve::closed_signed_area(&curve::resolve(&c.origin, &c.outline)).abs())
+        .sum();
+
+    let area = outline_area.abs() - cut_out_area;
+    if !area.is_finite() {
+        return Err(
+            AppError::invalid_geometry("Piece geometry has non-finite coordinates")
+                .with_piece(&piece.id),
+        );
+    }
+    Ok(area)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::engine::pattern_piece;
+    use crate::types::pattern::CutOut;
+
+    fn p(x: f64, y: f64) -> Point2D {
+        Point2D { x, y }
+    }
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: p(x, y),
+            edge: Default::default(),
+        }
+    }
+
+    fn cubic(c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) -> CurveSegment {
+        CurveSegment::CubicBezier {
+            control1: p(c1.0, c1.1),
+            control2: p(c2.0, c2.1),
+            end: p(end.0, end.1),
+            edge: Default::default(),
+        }
+    }
+
+    fn arc(center: (f64, f64), radius: f64, start_angle: f64, end_angle: f64) -> CurveSegment {
+        CurveSegment::Arc {
+            center: p(center.0, center.1),
+            radius,
+            start_angle,
+            end_angle,
+            edge: Default::default(),
+        }
+    }
+
+    fn piece(outline: Vec<CurveSegment>) -> PatternPieceData {
+        let mut piece = pattern_piece::create_default("Piece");
+        piece.outline = outline;
+        piece
+    }
+
+    fn square() -> PatternPieceData {
+        piece(vec![
+            line(100.0, 0.0),
+            line(100.0, 100.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ])
+    }
+
+    fn codes(piece: &PatternPieceData) -> Vec<(DiagnosticCode, Option<usize>)> {
+        validate(piece)
+            .into_iter()
+            .map(|d| (d.code, d.segment_index))
+            .collect()
+    }
+
+    #[test]
+    fn valid_outline_has_no_diagnostics() {
+        assert!(validate(&square()).is_empty());
+    }
+
+    #[test]
+    fn open_outline_is_reported_at_its_last_segment() {
+        let mut open = square();
+        open.outline[3] = line(0.0, 50.0);
+        assert_eq!(codes(&open), [(DiagnosticCode::OpenOutline, Some(3))]);
+    }
+
+    #[test]
+    fn zero_length_line_is_degenerate() {
+        let mut doubled = square();
+        doubled.outline.insert(1, line(100.0, 0.0));
+        assert_eq!(
+            codes(&doubled),
+            [(DiagnosticCode::DegenerateSegment, Some(1))]
+        );
+    }
+
+    #[test]
+    fn bow_tie_crosses_itself() {
+        let bow_tie = piece(vec![
+            line(100.0, 100.0),
+            line(100.0, 0.0),
+            line(0.0, 100.0),
+            line(0.0, 0.0),
+        ]);
+        assert_eq!(
+            codes(&bow_tie),
+            [(DiagnosticCode::SelfIntersection, Some(0))]
+        );
+    }
+
+    #[test]
+    fn crossing_curves_are_found() {
+        // Two S-curves running corner to corner cross in the middle.
+        let crossed = piece(vec![
+            cubic((40.0, 0.0), (60.0, 100.0), (100.0, 100.0)),
+            line(100.0, 0.0),
+            cubic((60.0, 0.0), (40.0, 100.0), (0.0, 100.0)),
+            line(0.0, 0.0),
+        ]);
+        let diagnostics = validate(&crossed);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::SelfIntersection);
+        assert_eq!(
+            diagnostics[0].message,
+            "Outline segment 0 crosses segment 2"
+        );
+        let at = diagnostics[0].location.clone().unwrap();
+        assert!((at.x - 50.0).abs() < 0.1 && (at.y - 50.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn circle_of_arcs_has_exact_area() {
+        let mut circle = piece(vec![
+            arc((0.0, 0.0), 50.0, 0.0, PI),
+            arc((0.0, 0.0), 50.0, PI, 2.0 * PI),
+        ]);
+        circle.origin = p(50.0, 0.0);
+        assert!(validate(&circle).is_empty());
+        assert!((compute_area(&circle).unwrap() - PI * 2500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cut_outs_are_subtracted_whatever_their_winding() {
+        let mut holed = square();
+        let hole = |end_angle| CutOut {
+            origin: p(60.0, 50.0),
+            outline: vec![arc((50.0, 50.0), 10.0, 0.0, end_angle)],
+        };
+        holed.cut_outs = vec![hole(2.0 * PI)];
+        let area = compute_area(&holed).unwrap();
+        assert!((area - (10000.0 - PI * 100.0)).abs() < 1e-6);
+        holed.cut_outs = vec![hole(-2.0 * PI)];
+        assert!((compute_area(&holed).unwrap() - area).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fold_pieces_are_measured_unfolded() {
+        // The left half of a 100 x 100 square, folded along its closing edge at x = 50.
+        let mut half = piece(vec![line(0.0, 0.0), line(0.0, 100.0), line(50.0, 100.0)]);
+        half.origin = p(50.0, 0.0);
+        assert!((compute_area(&half).unwrap() - 5000.0).abs() < 1e-9);
+        half.fold_edge = Some(3);
+        assert!((compute_area(&half).unwrap() - 10000.0).abs() < 1e-9);
+    }
 }
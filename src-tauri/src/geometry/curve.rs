@@ -0,0 +1,819 @@
+use super::transform::Transform2D;
+use super::vec2::Vec2;
+use crate::types::pattern::{CurveSegment, EdgeAttributes, Point2D};
+
+const MAX_FLATTEN_STEPS: usize = 1024;
+/// Largest sweep (radians) approximated by a single cubic when an arc is converted to Béziers.
+const MAX_CUBIC_ARC_SWEEP: f64 = std::f64::consts::FRAC_PI_2;
+/// Target absolute error (mm) of numerically integrated arc lengths.
+const LENGTH_TOLERANCE: f64 = 1e-6;
+/// Maximum bisection depth of the adaptive arc-length quadrature.
+const MAX_LENGTH_DEPTH: u32 = 24;
+
+/// Five-point Gauss–Legendre nodes and weights on [-1, 1].
+const GAUSS_LEGENDRE_5: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_889),
+    (-0.538_469_310_105_683, 0.478_628_670_499_366),
+    (0.538_469_310_105_683, 0.478_628_670_499_366),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189),
+    (0.906_179_845_938_664, 0.236_926_885_056_189),
+];
+
+/// A single outline segment resolved to absolute coordinates, with its start point made explicit.
+#[derive(Debug, Clone, Copy)]
+pub enum Curve {
+    Line {
+        from: Vec2,
+        to: Vec2,
+    },
+    Quadratic {
+        from: Vec2,
+        ctrl: Vec2,
+        to: Vec2,
+    },
+    Cubic {
+        from: Vec2,
+        ctrl1: Vec2,
+        ctrl2: Vec2,
+        to: Vec2,
+    },
+    Arc {
+        center: Vec2,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    },
+}
+
+impl Curve {
+    pub fn start(&self) -> Vec2 {
+        match self {
+            Curve::Line { from, .. }
+            | Curve::Quadratic { from, .. }
+            | Curve::Cubic { from, .. } => *from,
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                ..
+            } => *center + Vec2::new(start_angle.cos(), start_angle.sin()) * *radius,
+        }
+    }
+
+    pub fn end(&self) -> Vec2 {
+        match self {
+            Curve::Line { to, .. } | Curve::Quadratic { to, .. } | Curve::Cubic { to, .. } => *to,
+            Curve::Arc {
+                center,
+                radius,
+                end_angle,
+                ..
+            } => *center + Vec2::new(end_angle.cos(), end_angle.sin()) * *radius,
+        }
+    }
+
+    /// Evaluate the curve at parameter `t` in [0, 1].
+    pub fn point_at(&self, t: f64) -> Vec2 {
+        match self {
+            Curve::Line { from, to } => from.lerp(to, t),
+            Curve::Quadratic { from, ctrl, to } => {
+                let mt = 1.0 - t;
+                *from * (mt * mt) + *ctrl * (2.0 * mt * t) + *to * (t * t)
+            }
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let mt = 1.0 - t;
+                *from * (mt * mt * mt)
+                    + *ctrl1 * (3.0 * mt * mt * t)
+                    + *ctrl2 * (3.0 * mt * t * t)
+                    + *to * (t * t * t)
+            }
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let a = start_angle + (end_angle - start_angle) * t;
+                *center + Vec2::new(a.cos(), a.sin()) * *radius
+            }
+        }
+    }
+
+    /// First derivative with respect to `t`.
+    pub fn derivative_at(&self, t: f64) -> Vec2 {
+        match self {
+            Curve::Line { from, to } => *to - *from,
+            Curve::Quadratic { from, ctrl, to } => {
+                let mt = 1.0 - t;
+                (*ctrl - *from) * (2.0 * mt) + (*to - *ctrl) * (2.0 * t)
+            }
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let mt = 1.0 - t;
+                (*ctrl1 - *from) * (3.0 * mt * mt)
+                    + (*ctrl2 - *ctrl1) * (6.0 * mt * t)
+                    + (*to - *ctrl2) * (3.0 * t * t)
+            }
+            Curve::Arc {
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => {
+                let sweep = end_angle - start_angle;
+                let a = start_angle + sweep * t;
+                Vec2::new(-a.sin(), a.cos()) * (radius * sweep)
+            }
+        }
+    }
+
+    /// Unit tangent at `t`, falling back to the chord direction where the derivative vanishes
+    /// (e.g. a Bézier control point coinciding with an endpoint).
+    pub fn tangent_at(&self, t: f64) -> Vec2 {
+        let d = self.derivative_at(t);
+        if d.length() > 1e-9 {
+            return d.normalize();
+        }
+        let probe = if t < 0.5 { t + 1e-3 } else { t - 1e-3 };
+        let chord = if t < 0.5 {
+            self.point_at(probe) - self.point_at(t)
+        } else {
+            self.point_at(t) - self.point_at(probe)
+        };
+        chord.normalize()
+    }
+
+    /// Split at parameter `t` into the parts before and after it.
+    pub fn split(&self, t: f64) -> (Curve, Curve) {
+        match *self {
+            Curve::Line { from, to } => {
+                let p = from.lerp(&to, t);
+                (Curve::Line { from, to: p }, Curve::Line { from: p, to })
+            }
+            Curve::Quadratic { from, ctrl, to } => {
+                let a = from.lerp(&ctrl, t);
+                let b = ctrl.lerp(&to, t);
+                let p = a.lerp(&b, t);
+                (
+                    Curve::Quadratic {
+                        from,
+                        ctrl: a,
+                        to: p,
+                    },
+                    Curve::Quadratic {
+                        from: p,
+                        ctrl: b,
+                        to,
+                    },
+                )
+            }
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let a = from.lerp(&ctrl1, t);
+                let b = ctrl1.lerp(&ctrl2, t);
+                let c = ctrl2.lerp(&to, t);
+                let ab = a.lerp(&b, t);
+                let bc = b.lerp(&c, t);
+                let p = ab.lerp(&bc, t);
+                (
+                    Curve::Cubic {
+                        from,
+                        ctrl1: a,
+                        ctrl2: ab,
+                        to: p,
+                    },
+                    Curve::Cubic {
+                        from: p,
+                        ctrl1: bc,
+                        ctrl2: c,
+                        to,
+                    },
+                )
+            }
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let mid = start_angle + (end_angle - start_angle) * t;
+                (
+                    Curve::Arc {
+                        center,
+                        radius,
+                        start_angle,
+                        end_angle: mid,
+                    },
+                    Curve::Arc {
+                        center,
+                        radius,
+                        start_angle: mid,
+                        end_angle,
+                    },
+                )
+            }
+        }
+    }
+
+    /// The same curve traversed in the opposite direction.
+    pub fn reversed(&self) -> Curve {
+        match *self {
+            Curve::Line { from, to } => Curve::Line { from: to, to: from },
+            Curve::Quadratic { from, ctrl, to } => Curve::Quadratic {
+                from: to,
+                ctrl,
+                to: from,
+            },
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Curve::Cubic {
+                from: to,
+                ctrl1: ctrl2,
+                ctrl2: ctrl1,
+                to: from,
+            },
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => Curve::Arc {
+                center,
+                radius,
+                start_angle: end_angle,
+                end_angle: start_angle,
+            },
+        }
+    }
+
+    /// The mirror image of this curve across the line through `a` and `b`, traversed in the
+    /// same direction.
+    pub fn reflected(&self, a: Vec2, b: Vec2) -> Curve {
+        let reflect = |p: Vec2| p.reflected(&a, &b);
+        match *self {
+            Curve::Line { from, to } => Curve::Line {
+                from: reflect(from),
+                to: reflect(to),
+            },
+            Curve::Quadratic { from, ctrl, to } => Curve::Quadratic {
+                from: reflect(from),
+                ctrl: reflect(ctrl),
+                to: reflect(to),
+            },
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Curve::Cubic {
+                from: reflect(from),
+                ctrl1: reflect(ctrl1),
+                ctrl2: reflect(ctrl2),
+                to: reflect(to),
+            },
+            // Reflecting across a line at angle θ maps the direction at angle φ to 2θ − φ.
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let twice = 2.0 * (b - a).angle();
+                Curve::Arc {
+                    center: reflect(center),
+                    radius,
+                    start_angle: twice - start_angle,
+                    end_angle: twice - end_angle,
+                }
+            }
+        }
+    }
+
+    /// This curve mapped through `transform`. Béziers and lines map exactly. Arcs stay arcs
+    /// under transforms that preserve circles; otherwise they become cubic Béziers of at most
+    /// a quarter turn each, so one arc may come back as several curves of equal sweep.
+    pub fn transformed(&self, transform: &Transform2D) -> Vec<Curve> {
+        let map = |p: Vec2| transform.apply(&p);
+        match *self {
+            Curve::Line { from, to } => vec![Curve::Line {
+                from: map(from),
+                to: map(to),
+            }],
+            Curve::Quadratic { from, ctrl, to } => vec![Curve::Quadratic {
+                from: map(from),
+                ctrl: map(ctrl),
+                to: map(to),
+            }],
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => vec![Curve::Cubic {
+                from: map(from),
+                ctrl1: map(ctrl1),
+                ctrl2: map(ctrl2),
+                to: map(to),
+            }],
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => match transform.uniform_scale() {
+                Some(scale) => {
+                    // The linear part is a rotation by θ, possibly composed with a reflection,
+                    // which maps the direction at angle φ to θ + φ, or θ − φ when mirrored.
+                    let theta = transform.c.atan2(transform.a);
+                    let map_angle = |phi: f64| {
+                        if transform.is_reflection() {
+                            theta - phi
+                        } else {
+                            theta + phi
+                        }
+                    };
+                    vec![Curve::Arc {
+                        center: map(center),
+                        radius: radius * scale,
+                        start_angle: map_angle(start_angle),
+                        end_angle: map_angle(end_angle),
+                    }]
+                }
+                None => arc_to_cubics(center, radius, start_angle, end_angle)
+                    .iter()
+                    .flat_map(|c| c.transformed(transform))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Convert back to an outline segment. The start point is implied by the previous segment.
+    pub fn to_segment(self, edge: EdgeAttributes) -> CurveSegment {
+        match self {
+            Curve::Line { to, .. } => CurveSegment::Line {
+                end: to.into(),
+                edge,
+            },
+            Curve::Quadratic { ctrl, to, .. } => CurveSegment::QuadraticBezier {
+                control: ctrl.into(),
+                end: to.into(),
+                edge,
+            },
+            Curve::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => CurveSegment::CubicBezier {
+                control1: ctrl1.into(),
+                control2: ctrl2.into(),
+                end: to.into(),
+                edge,
+            },
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => CurveSegment::Arc {
+                center: center.into(),
+                radius,
+                start_angle,
+                end_angle,
+                edge,
+            },
+        }
+    }
+
+    /// Exact contribution of this curve to the signed area of a closed path it is part of:
+    /// the line integral ½∮(x dy − y dx) along the curve (Green's theorem).
+    pub fn area_integral(&self) -> f64 {
+        match *self {
+            Curve::Line { from, to } => 0.5 * from.cross(&to),
+            Curve::Quadratic { from, ctrl, to } => {
+                bezier_area_integral(&[from, (ctrl - from) * 2.0, from - ctrl * 2.0 + to])
+            }
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => bezier_area_integral(&[
+                from,
+                (ctrl1 - from) * 3.0,
+                (from - ctrl1 * 2.0 + ctrl2) * 3.0,
+                to - from + (ctrl1 - ctrl2) * 3.0,
+            ]),
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                // x dy − y dx = (r·cx·cosθ + r·cy·sinθ + r²) dθ
+                let (s0, c0) = start_angle.sin_cos();
+                let (s1, c1) = end_angle.sin_cos();
+                0.5 * (radius * center.x * (s1 - s0) - radius * center.y * (c1 - c0)
+                    + radius * radius * (end_angle - start_angle))
+            }
+        }
+    }
+
+    /// Arc length of the whole curve.
+    pub fn length(&self) -> f64 {
+        self.length_between(0.0, 1.0)
+    }
+
+    /// Arc length between parameters `t0` and `t1`. Exact for lines and arcs; Béziers are
+    /// integrated with adaptive Gauss–Legendre quadrature to within `LENGTH_TOLERANCE`.
+    pub fn length_between(&self, t0: f64, t1: f64) -> f64 {
+        match *self {
+            Curve::Line { from, to } => from.distance(&to) * (t1 - t0).abs(),
+            Curve::Arc {
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => radius.abs() * ((end_angle - start_angle) * (t1 - t0)).abs(),
+            Curve::Quadratic { .. } | Curve::Cubic { .. } => {
+                let (a, b) = if t0 <= t1 { (t0, t1) } else { (t1, t0) };
+                let whole = self.gauss_length(a, b);
+                self.adaptive_length(a, b, whole, LENGTH_TOLERANCE, MAX_LENGTH_DEPTH)
+            }
+        }
+    }
+
+    /// Parameter reached after travelling `distance` along the curve from `t0` towards
+    /// `t = 1`, clamped to 1 if the rest of the curve is shorter than that.
+    pub fn t_at_length(&self, t0: f64, distance: f64) -> f64 {
+        if distance <= 0.0 {
+            return t0;
+        }
+        match self {
+            Curve::Line { .. } | Curve::Arc { .. } => {
+                let total = self.length();
+                if total <= 0.0 {
+                    return t0;
+                }
+                (t0 + distance / total).min(1.0)
+            }
+            Curve::Quadratic { .. } | Curve::Cubic { .. } => {
+                if self.length_between(t0, 1.0) <= distance {
+                    return 1.0;
+                }
+                let (mut lo, mut hi) = (t0, 1.0);
+                while hi - lo > 1e-10 {
+                    let mid = 0.5 * (lo + hi);
+                    if self.length_between(t0, mid) < distance {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                0.5 * (lo + hi)
+            }
+        }
+    }
+
+    fn gauss_length(&self, a: f64, b: f64) -> f64 {
+        let half = 0.5 * (b - a);
+        let mid = 0.5 * (a + b);
+        GAUSS_LEGENDRE_5
+            .iter()
+            .map(|&(x, w)| w * self.derivative_at(mid + half * x).length())
+            .sum::<f64>()
+            * half
+    }
+
+    fn adaptive_length(&self, a: f64, b: f64, whole: f64, tolerance: f64, depth: u32) -> f64 {
+        let mid = 0.5 * (a + b);
+        let left = self.gauss_length(a, mid);
+        let right = self.gauss_length(mid, b);
+        if depth == 0 || (left + right - whole).abs() <= tolerance {
+            return left + right;
+        }
+        self.adaptive_length(a, mid, left, tolerance * 0.5, depth - 1)
+            + self.adaptive_length(mid, b, right, tolerance * 0.5, depth - 1)
+    }
+
+    /// Parameter of the point on the curve closest to `p`, and its distance from `p`.
+    pub fn closest_t(&self, p: Vec2) -> (f64, f64) {
+        const SAMPLES: usize = 64;
+        let dist = |t: f64| self.point_at(t).distance(&p);
+        let mut best = (0..=SAMPLES)
+            .map(|i| i as f64 / SAMPLES as f64)
+            .min_by(|a, b| dist(*a).total_cmp(&dist(*b)))
+            .unwrap_or(0.0);
+
+        // Golden-section refinement within the neighbouring samples.
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let step = 1.0 / SAMPLES as f64;
+        let (mut lo, mut hi) = ((best - step).max(0.0), (best + step).min(1.0));
+        while hi - lo > 1e-10 {
+            let a = hi - (hi - lo) * ratio;
+            let b = lo + (hi - lo) * ratio;
+            if dist(a) < dist(b) {
+                hi = b;
+            } else {
+                lo = a;
+            }
+        }
+        let refined = 0.5 * (lo + hi);
+        if dist(refined) < dist(best) {
+            best = refined;
+        }
+        (best, dist(best))
+    }
+
+    /// Length of the flattened polyline, accurate to roughly `tolerance`.
+    pub fn flattened_length(&self, tolerance: f64) -> f64 {
+        self.flatten(tolerance)
+            .windows(2)
+            .map(|p| p[0].distance(&p[1]))
+            .sum()
+    }
+
+    /// Flatten into a polyline whose maximum deviation from the curve is at most `tolerance`.
+    /// The returned points include both endpoints.
+    pub fn flatten(&self, tolerance: f64) -> Vec<Vec2> {
+        let steps = self.flatten_steps(tolerance);
+        (0..=steps)
+            .map(|i| self.point_at(i as f64 / steps as f64))
+            .collect()
+    }
+
+    fn flatten_steps(&self, tolerance: f64) -> usize {
+        let tol = tolerance.max(1e-6);
+        let steps = match self {
+            Curve::Line { .. } => 1.0,
+            // Uniform subdivision error is bounded by max|B''| / (8 n²).
+            Curve::Quadratic { from, ctrl, to } => {
+                let dd = (*from - *ctrl * 2.0 + *to).length() * 2.0;
+                (dd / (8.0 * tol)).sqrt().ceil()
+            }
+            Curve::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let dd0 = (*from - *ctrl1 * 2.0 + *ctrl2).length();
+                let dd1 = (*ctrl1 - *ctrl2 * 2.0 + *to).length();
+                (6.0 * dd0.max(dd1) / (8.0 * tol)).sqrt().ceil()
+            }
+            Curve::Arc {
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => {
+                let sweep = (end_angle - start_angle).abs();
+                if *radius <= tol {
+                    1.0
+                } else {
+                    let max_step = 2.0 * (1.0 - tol / radius).acos();
+                    (sweep / max_step).ceil()
+                }
+            }
+        };
+        (steps as usize).clamp(1, MAX_FLATTEN_STEPS)
+    }
+}
+
+/// Resolve origin-anchored outline segments into absolute curves.
+/// The path starts at `origin`; each segment starts where the previous one ended,
+/// except arcs, which carry their own start point.
+pub fn resolve(origin: &Point2D, segments: &[CurveSegment]) -> Vec<Curve> {
+    let mut current = Vec2::from(origin);
+    let mut curves = Vec::with_capacity(segments.len());
+
+    for seg in segments {
+        let curve = match seg {
+            CurveSegment::Line { end, .. } => Curve::Line {
+                from: current,
+                to: end.into(),
+            },
+            CurveSegment::QuadraticBezier { control, end, .. } => Curve::Quadratic {
+                from: current,
+                ctrl: control.into(),
+                to: end.into(),
+            },
+            CurveSegment::CubicBezier {
+                control1,
+                control2,
+                end,
+                ..
+            } => Curve::Cubic {
+                from: current,
+                ctrl1: control1.into(),
+                ctrl2: control2.into(),
+                to: end.into(),
+            },
+            CurveSegment::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => Curve::Arc {
+                center: center.into(),
+                radius: *radius,
+                start_angle: *start_angle,
+                end_angle: *end_angle,
+            },
+        };
+        current = curve.end();
+        curves.push(curve);
+    }
+
+    curves
+}
+
+/// Like `resolve`, followed by the implicit straight edge back to `origin` when the last
+/// segment ends more than `close_tolerance` away from it.
+pub fn resolve_closed(
+    origin: &Point2D,
+    segments: &[CurveSegment],
+    close_tolerance: f64,
+) -> Vec<Curve> {
+    let mut curves = resolve(origin, segments);
+    let start = Vec2::from(origin);
+    if let Some(end) = curves.last().map(Curve::end) {
+        if end.distance(&start) > close_tolerance {
+            curves.push(Curve::Line {
+                from: end,
+                to: start,
+            });
+        }
+    }
+    curves
+}
+
+/// Index and parameter of the point on `curves` closest to `p`.
+pub fn closest_position(curves: &[Curve], p: Vec2) -> Option<(usize, f64)> {
+    curves
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let (t, d) = c.closest_t(p);
+            (i, t, d)
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(i, t, _)| (i, t))
+}
+
+/// Cubic Béziers approximating an arc, one per equal slice of at most `MAX_CUBIC_ARC_SWEEP`.
+fn arc_to_cubics(center: Vec2, radius: f64, start_angle: f64, end_angle: f64) -> Vec<Curve> {
+    let sweep = end_angle - start_angle;
+    let count = (sweep.abs() / MAX_CUBIC_ARC_SWEEP).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    // Handle length that makes the cubic's midpoint lie on the circle.
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    (0..count)
+        .map(|i| {
+            let a0 = start_angle + step * i as f64;
+            let a1 = a0 + step;
+            let (u0, u1) = (Vec2::new(a0.cos(), a0.sin()), Vec2::new(a1.cos(), a1.sin()));
+            let (from, to) = (center + u0 * radius, center + u1 * radius);
+            Curve::Cubic {
+                from,
+                ctrl1: from + u0.perpendicular() * k,
+                ctrl2: to - u1.perpendicular() * k,
+                to,
+            }
+        })
+        .collect()
+}
+
+/// ½∫₀¹ B(t) × B′(t) dt for a polynomial curve B(t) = Σ aᵢ tⁱ given by its power-basis
+/// coefficients. Expanding the integrand gives Σ_{i<j} (aᵢ × aⱼ)(j − i)/(i + j).
+fn bezier_area_integral(coeffs: &[Vec2]) -> f64 {
+    let mut sum = 0.0;
+    for (i, a) in coeffs.iter().enumerate() {
+        for (j, b) in coeffs.iter().enumerate().skip(i + 1) {
+            sum += a.cross(b) * (j - i) as f64 / (i + j) as f64;
+        }
+    }
+    0.5 * sum
+}
+
+/// Exact signed area enclosed by a sequence of curves, with the same sign convention as
+/// `polygon_signed_area`. The path is closed with straight lines across any gap between one curve's end
+/// and the next curve's start, including from the last curve back to the first.
+pub fn closed_signed_area(curves: &[Curve]) -> f64 {
+    let n = curves.len();
+    let mut area = 0.0;
+    for (i, c) in curves.iter().enumerate() {
+        area += c.area_integral();
+        let next_start = curves[(i + 1) % n].start();
+        area += 0.5 * c.end().cross(&next_start);
+    }
+    area
+}
+
+/// Signed area of a closed polygon (shoelace). Positive for counter-clockwise winding in a Y-up frame.
+pub fn polygon_signed_area(points: &[Vec2]) -> f64 {
+    let n = points.len();
+    if n < 3 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    for i in 0..n {
+        sum += points[i].cross(&points[(i + 1) % n]);
+    }
+    sum * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{PI, TAU};
+
+    use super::*;
+
+    fn p(x: f64, y: f64) -> Point2D {
+        Point2D { x, y }
+    }
+
+    fn line(x: f64, y: f64) -> CurveSegment {
+        CurveSegment::Line {
+            end: p(x, y),
+            edge: EdgeAttributes::default(),
+        }
+    }
+
+    #[test]
+    fn resolve_chains_segments_from_the_origin() {
+        let curves = resolve(&p(0.0, 0.0), &[line(100.0, 0.0), line(100.0, 50.0)]);
+        assert_eq!(curves.len(), 2);
+        assert_eq!(curves[1].start(), Vec2::new(100.0, 0.0));
+        assert_eq!(curves[1].end(), Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn resolve_closed_adds_the_closing_edge_past_the_tolerance() {
+        let open = [line(100.0, 0.0), line(100.0, 50.0)];
+        let curves = resolve_closed(&p(0.0, 0.0), &open, 0.01);
+        assert_eq!(curves.len(), 3);
+        assert_eq!(curves[2].end(), Vec2::new(0.0, 0.0));
+
+        let nearly_closed = [line(100.0, 0.0), line(100.0, 50.0), line(0.0, 0.005)];
+        assert_eq!(resolve_closed(&p(0.0, 0.0), &nearly_closed, 0.01).len(), 3);
+    }
+
+    #[test]
+    fn flattened_arc_stays_within_tolerance() {
+        let arc = Curve::Arc {
+            center: Vec2::new(10.0, 20.0),
+            radius: 50.0,
+            start_angle: 0.0,
+            end_angle: PI,
+        };
+        let points = arc.flatten(0.02);
+        assert_eq!(points[0], arc.start());
+        assert_eq!(points[points.len() - 1], arc.end());
+        for pair in points.windows(2) {
+            let mid = pair[0].lerp(&pair[1], 0.5);
+            assert!(50.0 - mid.distance(&Vec2::new(10.0, 20.0)) <= 0.02 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn split_cubic_meets_at_the_split_point() {
+        let cubic = Curve::Cubic {
+            from: Vec2::new(0.0, 0.0),
+            ctrl1: Vec2::new(10.0, 40.0),
+            ctrl2: Vec2::new(60.0, -20.0),
+            to: Vec2::new(80.0, 10.0),
+        };
+        let (before, after) = cubic.split(0.3);
+        let at = cubic.point_at(0.3);
+        assert!(before.end().distance(&at) < 1e-9);
+        assert!(after.start().distance(&at) < 1e-9);
+        assert!((before.length() + after.length() - cubic.length()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn circle_area_is_exact() {
+        let circle = Curve::Arc {
+            center: Vec2::new(0.0, 0.0),
+            radius: 30.0,
+            start_angle: 0.0,
+            end_angle: TAU,
+        };
+        assert!((closed_signed_area(&[circle]) - PI * 900.0).abs() < 1e-9);
+        assert!((closed_signed_area(&[circle.reversed()]) + PI * 900.0).abs() < 1e-9);
+    }
+}
@@ -1,8 +1,11 @@
 use super::vec2::Vec2;
 
-/// Line-line intersection between segment (p1,p2) and segment (p3,p4).
-/// Returns the intersection point if the segments intersect.
-pub fn segment_segment(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> Option<Vec2> {
+/// Crossings this close to the end of an edge are attributed to the following edge.
+const EDGE_END_EPS: f64 = 1e-9;
+
+/// Intersection of segment (p1,p2) with segment (p3,p4), as the parameters `(t, u)` of the
+/// intersection along each.
+pub fn segment_segment_params(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> Option<(f64, f64)> {
     let d1 = p2 - p1;
     let d2 = p4 - p3;
     let cross = d1.cross(&d2);
@@ -16,29 +19,56 @@ pub fn segment_segment(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> Option<Vec2> {
     let u = d3.cross(&d1) / cross;
 
     if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
-        Some(p1 + d1 * t)
+        Some((t, u))
     } else {
         None
     }
 }
 
-/// Ray-segment intersection. Ray starts at `origin` in `direction`.
-/// Returns parameter t along the ray if intersection exists (t >= 0).
-pub fn ray_segment(origin: Vec2, direction: Vec2, p1: Vec2, p2: Vec2) -> Option<f64> {
-    let d = p2 - p1;
-    let cross = direction.cross(&d);
-
+/// Intersection of two infinite lines, each given by a point and a direction.
+pub fn line_line(p1: Vec2, d1: Vec2, p2: Vec2, d2: Vec2) -> Option<Vec2> {
+    let cross = d1.cross(&d2);
     if cross.abs() < f64::EPSILON {
         return None;
     }
+    let t = (p2 - p1).cross(&d2) / cross;
+    Some(p1 + d1 * t)
+}
 
-    let d2 = p1 - origin;
-    let t = d2.cross(&d) / cross;
-    let u = d2.cross(&direction) / cross;
+/// All proper crossings between non-adjacent edges of a closed polygon, as
+/// `(edge_i, edge_j, t_i, t_j)`. Each crossing is reported once, on half-open edges.
+pub fn polygon_self_intersections(points: &[Vec2]) -> Vec<(usize, usize, f64, f64)> {
+    let n = points.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    let min_x = |i: usize| points[i].x.min(points[(i + 1) % n].x);
+    order.sort_by(|&a, &b| min_x(a).total_cmp(&min_x(b)));
 
-    if t >= 0.0 && (0.0..=1.0).contains(&u) {
-        Some(t)
-    } else {
-        None
+    let mut found = Vec::new();
+    for (k, &i) in order.iter().enumerate() {
+        let (a0, a1) = (points[i], points[(i + 1) % n]);
+        let max_x = a0.x.max(a1.x);
+        let (a_min_y, a_max_y) = (a0.y.min(a1.y), a0.y.max(a1.y));
+        for &j in &order[k + 1..] {
+            if min_x(j) > max_x {
+                break;
+            }
+            if (i + 1) % n == j || (j + 1) % n == i {
+                continue;
+            }
+            let (b0, b1) = (points[j], points[(j + 1) % n]);
+            if b0.y.max(b1.y) < a_min_y || b0.y.min(b1.y) > a_max_y {
+                continue;
+            }
+            if let Some((t, u)) = segment_segment_params(a0, a1, b0, b1) {
+                if t < 1.0 - EDGE_END_EPS && u < 1.0 - EDGE_END_EPS {
+                    if i < j {
+                        found.push((i, j, t, u));
+                    } else {
+                        found.push((j, i, u, t));
+                    }
+                }
+            }
+        }
     }
+    found
 }
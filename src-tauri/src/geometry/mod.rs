@@ -1,9 +1,9 @@
 #[allow(dead_code)]
 pub mod bbox;
-#[allow(dead_code)]
+pub mod curve;
 pub mod intersection;
+pub mod offset;
 pub mod tessellation;
-#[allow(dead_code)]
 pub mod transform;
 #[allow(dead_code)]
 pub mod vec2;
@@ -0,0 +1,394 @@
+use super::curve::polygon_signed_area;
+use super::intersection;
+use super::vec2::Vec2;
+use crate::types::error::{AppError, AppResult};
+use crate::types::pattern::CornerStyle;
+
+/// Miters longer than this multiple of the offset distance are bevelled instead.
+const MITER_LIMIT: f64 = 4.0;
+/// Maximum chord error when rounding joins inside a run.
+const ROUND_TOLERANCE: f64 = 0.02;
+const EPS: f64 = 1e-9;
+
+/// An open polyline that is offset as one unit.
+/// Joins inside a run are rounded (the exact offset of a smooth curve);
+/// joins between consecutive runs are treated as corners, finished with `corner`.
+#[derive(Debug, Clone)]
+pub struct OffsetRun {
+    pub points: Vec<Vec2>,
+    pub distance: f64,
+    /// Treatment of the corner at the end of this run.
+    pub corner: CornerStyle,
+}
+
+struct RunOffset {
+    path: Vec<Vec2>,
+    first_dir: Vec2,
+    last_dir: Vec2,
+    distance: f64,
+    corner: CornerStyle,
+}
+
+/// Offset a closed contour, given as consecutive runs, outward by each run's distance.
+/// Returns the outer boundary of the offset region, with loops created at concave
+/// corners or tight curves removed. The result keeps the winding of the input.
+pub fn offset_closed(runs: &[OffsetRun]) -> AppResult<Vec<Vec2>> {
+    let contour: Vec<Vec2> = runs.iter().flat_map(|r| r.points.iter().copied()).collect();
+    let area = polygon_signed_area(&dedup_closed(&contour));
+    if area.abs() < EPS {
+        return Err(AppError::invalid_geometry("Outline encloses no area"));
+    }
+    let side = area.signum();
+
+    let offsets: Vec<RunOffset> = runs
+        .iter()
+        .filter_map(|run| offset_run(run, side))
+        .collect();
+    if offsets.is_empty() {
+        return Err(AppError::invalid_geometry(
+            "Outline has no non-degenerate edges",
+        ));
+    }
+
+    let mut raw = Vec::new();
+    for (i, run) in offsets.iter().enumerate() {
+        raw.extend_from_slice(&run.path);
+        let next = &offsets[(i + 1) % offsets.len()];
+        raw.extend(corner_join(run, next, side));
+    }
+
+    let raw = dedup_closed(&raw);
+    let boundary = outer_boundary(&raw, side)?;
+    Ok(simplify_closed(&boundary))
+}
+
+/// Outward unit normal of an edge direction for a contour with the given winding sign.
+fn outward_normal(dir: Vec2, side: f64) -> Vec2 {
+    Vec2::new(dir.y, -dir.x) * side
+}
+
+fn offset_run(run: &OffsetRun, side: f64) -> Option<RunOffset> {
+    let w = run.distance;
+    let edges: Vec<(Vec2, Vec2, Vec2)> = run
+        .points
+        .windows(2)
+        .filter(|p| p[0].distance(&p[1]) > EPS)
+        .map(|p| (p[0], p[1], (p[1] - p[0]).normalize()))
+        .collect();
+    let first = edges.first()?;
+    let last = edges.last()?;
+
+    let mut path = Vec::with_capacity(edges.len() * 2);
+    for (i, &(a, b, dir)) in edges.iter().enumerate() {
+        let n = outward_normal(dir, side) * w;
+        if i > 0 {
+            let prev_dir = edges[i - 1].2;
+            let turn = prev_dir.cross(&dir) * side;
+            if turn > EPS && w > EPS {
+                // Convex: fill the gap with an arc around the shared vertex.
+                let n0 = outward_normal(prev_dir, side);
+                let n1 = outward_normal(dir, side);
+                path.extend(round_join(a, n0, n1, w));
+            }
+        }
+        path.push(a + n);
+        path.push(b + n);
+    }
+
+    Some(RunOffset {
+        path,
+        first_dir: first.2,
+        last_dir: last.2,
+        distance: w,
+        corner: run.corner,
+    })
+}
+
+/// Interior points of an arc of radius `w` around `center`, sweeping from normal `n0` to `n1`.
+fn round_join(center: Vec2, n0: Vec2, n1: Vec2, w: f64) -> Vec<Vec2> {
+    let sweep = n0.angle_between(&n1);
+    let max_step = if w > ROUND_TOLERANCE {
+        2.0 * (1.0 - ROUND_TOLERANCE / w).acos()
+    } else {
+        sweep.abs()
+    };
+    let steps = (sweep.abs() / max_step).ceil().max(1.0) as usize;
+    (1..steps)
+        .map(|i| center + n0.rotate(sweep * i as f64 / steps as f64) * w)
+        .collect()
+}
+
+/// Points joining the end of `prev` to the start of `next` at a convex corner, shaped by
+/// `prev.corner`. Miters past the miter limit fall back to a bevel. Concave corners are
+/// connected directly and the resulting loop is removed by `outer_boundary`.
+fn corner_join(prev: &RunOffset, next: &RunOffset, side: f64) -> Vec<Vec2> {
+    let (Some(&a), Some(&b)) = (prev.path.last(), next.path.first()) else {
+        return Vec::new();
+    };
+    let (da, db) = (prev.last_dir, next.first_dir);
+    let turn = da.cross(&db) * side;
+    if turn <= EPS || a.distance(&b) < EPS {
+        return Vec::new();
+    }
+
+    let vertex = a - outward_normal(da, side) * prev.distance;
+    let limit = MITER_LIMIT * prev.distance.max(next.distance);
+    let miter = intersection::line_line(a, da, b, db).filter(|m| m.distance(&vertex) <= limit);
+
+    match prev.corner {
+        CornerStyle::Miter => miter.into_iter().collect(),
+        CornerStyle::Bevel => Vec::new(),
+        CornerStyle::Square => match miter {
+            Some(m) if (m - a).dot(&da) <= next.distance + EPS => vec![m],
+            _ => vec![a + da * next.distance, b - db * prev.distance],
+        },
+        CornerStyle::HemIncoming => match hem_fold(vertex, da, a, b, db) {
+            Some((fold, end)) => vec![end, fold],
+            None => miter.into_iter().collect(),
+        },
+        CornerStyle::HemOutgoing => match hem_fold(vertex, db, b, a, da) {
+            Some((fold, end)) => vec![fold, end],
+            None => miter.into_iter().collect(),
+        },
+    }
+}
+
+/// Mirrored hem corner. The side allowance line (through `side_point` along `side_dir`) is
+/// reflected across the hem fold line (through `vertex` along `hem_dir`) and followed until it
+/// meets the hem allowance line (through `hem_point`). Returns the point where the side
+/// allowance crosses the fold line and the point where its mirror meets the hem allowance.
+fn hem_fold(
+    vertex: Vec2,
+    hem_dir: Vec2,
+    hem_point: Vec2,
+    side_point: Vec2,
+    side_dir: Vec2,
+) -> Option<(Vec2, Vec2)> {
+    let fold = intersection::line_line(side_point, side_dir, vertex, hem_dir)?;
+    let mirrored_dir = hem_dir * (2.0 * side_dir.dot(&hem_dir)) - side_dir;
+    let end = intersection::line_line(fold, mirrored_dir, hem_point, hem_dir)?;
+    Some((fold, end))
+}
+
+/// Trace the outer boundary of a closed, possibly self-intersecting polygon.
+/// Walks from the left-most vertex (always on the outer boundary) and, at every
+/// crossing, takes the branch that keeps the rest of the polygon on the interior side.
+/// Fails if the walk never gets back to its start, which degenerate overlaps can cause.
+pub fn outer_boundary(points: &[Vec2], side: f64) -> AppResult<Vec<Vec2>> {
+    let n = points.len();
+    if n < 4 {
+        return Ok(points.to_vec());
+    }
+
+    // Per-edge crossings as (parameter along edge, crossing id).
+    let mut edge_crossings: Vec<Vec<(f64, usize)>> = vec![Vec::new(); n];
+    let mut crossing_count = 0;
+    for (i, j, t, u) in intersection::polygon_self_intersections(points) {
+        edge_crossings[i].push((t, crossing_count));
+        edge_crossings[j].push((u, crossing_count));
+        crossing_count += 1;
+    }
+    if crossing_count == 0 {
+        return Ok(points.to_vec());
+    }
+
+    // Flatten into a sequence of entries with crossings spliced in.
+    let mut entries: Vec<(Vec2, Option<usize>)> = Vec::with_capacity(n + 2 * crossing_count);
+    let mut partners = vec![Vec::with_capacity(2); crossing_count];
+    let mut start = 0;
+    let mut leftmost = points[0];
+    for i in 0..n {
+        let a = points[i];
+        let b = points[(i + 1) % n];
+        if a.x < leftmost.x || (a.x == leftmost.x && a.y < leftmost.y) {
+            leftmost = a;
+            start = entries.len();
+        }
+        entries.push((a, None));
+        let crossings = &mut edge_crossings[i];
+        crossings.sort_by(|x, y| x.0.total_cmp(&y.0));
+        for &(t, id) in crossings.iter() {
+            partners[id].push(entries.len());
+            entries.push((a.lerp(&b, t), Some(id)));
+        }
+    }
+
+    let m = entries.len();
+    let next_distinct = |e: usize| -> usize {
+        let mut k = (e + 1) % m;
+        for _ in 0..m {
+            if entries[k].0.distance(&entries[e].0) > EPS {
+                break;
+            }
+            k = (k + 1) % m;
+        }
+        k
+    };
+
+    let mut result: Vec<Vec2> = Vec::new();
+    let mut e = start;
+    for _ in 0..=2 * m {
+        let pos = entries[e].0;
+        let mut current = e;
+        let prev = result
+            .iter()
+            .rev()
+            .find(|p| p.distance(&pos) > EPS)
+            .copied();
+        if let (Some(id), Some(prev)) = (entries[e].1, prev) {
+            let other = partners[id].iter().copied().find(|&k| k != e).unwrap_or(e);
+            let din = pos - prev;
+            let d_self = entries[next_distinct(e)].0 - pos;
+            let d_other = entries[next_distinct(other)].0 - pos;
+            let a_self = din.angle_between(&d_self) * side;
+            let a_other = din.angle_between(&d_other) * side;
+            if a_other < a_self {
+                current = other;
+            }
+        }
+        if result.last().is_none_or(|p| p.distance(&pos) > EPS) {
+            result.push(pos);
+        }
+        e = (current + 1) % m;
+        if e == start {
+            return Ok(result);
+        }
+    }
+
+    Err(AppError::invalid_geometry(
+        "Could not trace the outer boundary of the offset outline",
+    ))
+}
+
+/// Drop consecutive duplicate points, including a closing point equal to the first.
+fn dedup_closed(points: &[Vec2]) -> Vec<Vec2> {
+    let mut out: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &p in points {
+        if out.last().is_none_or(|q| q.distance(&p) > EPS) {
+            out.push(p);
+        }
+    }
+    while out.len() > 1 && out[0].distance(&out[out.len() - 1]) <= EPS {
+        out.pop();
+    }
+    out
+}
+
+/// Remove duplicate and collinear vertices, including zero-width spikes, from a closed polygon.
+pub fn simplify_closed(points: &[Vec2]) -> Vec<Vec2> {
+    let mut pts = dedup_closed(points);
+    let mut changed = true;
+    while changed && pts.len() > 3 {
+        changed = false;
+        let mut out = Vec::with_capacity(pts.len());
+        let n = pts.len();
+        for i in 0..n {
+            let prev = out.last().copied().unwrap_or(pts[(i + n - 1) % n]);
+            let cur = pts[i];
+            let next = pts[(i + 1) % n];
+            let chord = next - prev;
+            let deviation = (cur - prev).cross(&chord).abs() / chord.length().max(EPS);
+            if deviation < 1e-6 && out.len() + (n - i) > 3 {
+                changed = true;
+            } else {
+                out.push(cur);
+            }
+        }
+        pts = out;
+    }
+    pts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One run per edge of the closed polygon `points`, offset by the matching width.
+    fn edge_runs(points: &[(f64, f64)], widths: &[f64]) -> Vec<OffsetRun> {
+        let n = points.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (points[i], points[(i + 1) % n]);
+                OffsetRun {
+                    points: vec![Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)],
+                    distance: widths[i],
+                    corner: CornerStyle::Miter,
+                }
+            })
+            .collect()
+    }
+
+    fn assert_vertices(result: &[Vec2], expected: &[(f64, f64)]) {
+        assert_eq!(result.len(), expected.len(), "{result:?}");
+        for &(x, y) in expected {
+            assert!(
+                result.iter().any(|p| p.distance(&Vec2::new(x, y)) < 1e-6),
+                "({x}, {y}) missing from {result:?}"
+            );
+        }
+    }
+
+    const SQUARE: [(f64, f64); 4] = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+
+    #[test]
+    fn square_grows_by_the_distance() {
+        let result = offset_closed(&edge_runs(&SQUARE, &[10.0; 4])).unwrap();
+        assert_vertices(
+            &result,
+            &[
+                (-10.0, -10.0),
+                (110.0, -10.0),
+                (110.0, 110.0),
+                (-10.0, 110.0),
+            ],
+        );
+        assert!(polygon_signed_area(&result) > 0.0);
+    }
+
+    #[test]
+    fn clockwise_square_keeps_its_winding() {
+        let clockwise: Vec<_> = SQUARE.iter().rev().copied().collect();
+        let result = offset_closed(&edge_runs(&clockwise, &[10.0; 4])).unwrap();
+        assert!((polygon_signed_area(&result) + 14400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn concave_corner_loop_is_trimmed() {
+        let l_shape = [
+            (0.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 40.0),
+            (40.0, 40.0),
+            (40.0, 100.0),
+            (0.0, 100.0),
+        ];
+        let result = offset_closed(&edge_runs(&l_shape, &[10.0; 6])).unwrap();
+        assert_vertices(
+            &result,
+            &[
+                (-10.0, -10.0),
+                (110.0, -10.0),
+                (110.0, 50.0),
+                (50.0, 50.0),
+                (50.0, 110.0),
+                (-10.0, 110.0),
+            ],
+        );
+        assert!(intersection::polygon_self_intersections(&result).is_empty());
+    }
+
+    #[test]
+    fn mixed_widths_meet_where_offset_lines_cross() {
+        let result = offset_closed(&edge_runs(&SQUARE, &[30.0, 20.0, 10.0, 0.0])).unwrap();
+        assert_vertices(
+            &result,
+            &[(0.0, -30.0), (120.0, -30.0), (120.0, 110.0), (0.0, 110.0)],
+        );
+    }
+
+    #[test]
+    fn degenerate_outline_is_rejected() {
+        let flat = [(0.0, 0.0), (100.0, 0.0), (50.0, 0.0)];
+        assert!(offset_closed(&edge_runs(&flat, &[10.0; 3])).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use lyon_path::math::point;
 use lyon_path::Path;
 use lyon_tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers};
 
+use crate::types::error::{AppError, AppResult};
 use crate::types::pattern::{CurveSegment, Point2D};
 
 /// Tessellate a closed 2D path into triangle mesh data.
@@ -9,9 +10,11 @@ use crate::types::pattern::{CurveSegment, Point2D};
 pub fn tessellate_outline(
     origin: &Point2D,
     segments: &[CurveSegment],
-) -> Result<(Vec<f32>, Vec<u32>), String> {
+) -> AppResult<(Vec<f32>, Vec<u32>)> {
     if segments.is_empty() {
-        return Err("Cannot tessellate empty outline".to_string());
+        return Err(AppError::invalid_geometry(
+            "Cannot tessellate empty outline",
+        ));
     }
 
     let mut builder = Path::builder();
@@ -19,10 +22,10 @@ pub fn tessellate_outline(
 
     for seg in segments {
         match seg {
-            CurveSegment::Line { end } => {
+            CurveSegment::Line { end, .. } => {
                 builder.line_to(point(end.x as f32, end.y as f32));
             }
-            CurveSegment::QuadraticBezier { control, end } => {
+            CurveSegment::QuadraticBezier { control, end, .. } => {
                 builder.quadratic_bezier_to(
                     point(control.x as f32, control.y as f32),
                     point(end.x as f32, end.y as f32),
@@ -32,6 +35,7 @@ pub fn tessellate_outline(
                 control1,
                 control2,
                 end,
+                ..
             } => {
                 builder.cubic_bezier_to(
                     point(control1.x as f32, control1.y as f32),
@@ -44,6 +48,7 @@ pub fn tessellate_outline(
                 radius,
                 start_angle,
                 end_angle,
+                ..
             } => {
                 // Approximate arc with line segments
                 let steps = 32;
@@ -73,7 +78,7 @@ pub fn tessellate_outline(
                 [p.x, p.y, 0.0]
             }),
         )
-        .map_err(|e| format!("Tessellation failed: {e:?}"))?;
+        .map_err(|e| AppError::invalid_geometry(format!("Tessellation failed: {e:?}")))?;
 
     let positions: Vec<f32> = buffers
         .vertices
@@ -1,8 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use super::vec2::Vec2;
 
+/// Relative tolerance used to recognise a transform that preserves shape.
+const SIMILARITY_TOLERANCE: f64 = 1e-9;
+
 /// 2D affine transform represented as a 3x3 homogeneous matrix.
 /// Stored row-major: [[a, b, tx], [c, d, ty], [0, 0, 1]]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Transform2D {
     pub a: f64,
     pub b: f64,
@@ -74,6 +79,33 @@ impl Transform2D {
         )
     }
 
+    /// Apply only the linear part, for directions and offsets.
+    pub fn apply_vector(&self, v: &Vec2) -> Vec2 {
+        Vec2::new(self.a * v.x + self.b * v.y, self.c * v.x + self.d * v.y)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Whether the transform mirrors shapes, reversing the winding of outlines.
+    pub fn is_reflection(&self) -> bool {
+        self.determinant() < 0.0
+    }
+
+    /// The scale factor of a transform that maps circles to circles (rotation, reflection,
+    /// uniform scale and translation), or `None` if it distorts them.
+    pub fn uniform_scale(&self) -> Option<f64> {
+        let scale = self.determinant().abs().sqrt();
+        let tolerance = SIMILARITY_TOLERANCE * scale.max(1.0);
+        let (p, q) = if self.is_reflection() {
+            (self.a + self.d, self.b - self.c)
+        } else {
+            (self.a - self.d, self.b + self.c)
+        };
+        (scale > 0.0 && p.abs() <= tolerance && q.abs() <= tolerance).then_some(scale)
+    }
+
     pub fn then(&self, other: &Self) -> Self {
         Self {
             a: self.a * other.a + self.b * other.c,
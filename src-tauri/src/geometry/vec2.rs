@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::pattern::Point2D;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Vec2 {
     pub x: f64,
@@ -71,6 +73,13 @@ impl Vec2 {
         }
     }
 
+    /// Mirror image across the line through `a` and `b`.
+    pub fn reflected(&self, a: &Self, b: &Self) -> Self {
+        let axis = (*b - *a).normalize();
+        let d = *self - *a;
+        *a + axis * (2.0 * d.dot(&axis)) - d
+    }
+
     pub fn rotate(&self, angle: f64) -> Self {
         let cos = angle.cos();
         let sin = angle.sin();
@@ -81,6 +90,18 @@ impl Vec2 {
     }
 }
 
+impl From<&Point2D> for Vec2 {
+    fn from(p: &Point2D) -> Self {
+        Self { x: p.x, y: p.y }
+    }
+}
+
+impl From<Vec2> for Point2D {
+    fn from(v: Vec2) -> Self {
+        Self { x: v.x, y: v.y }
+    }
+}
+
 impl std::ops::Add for Vec2 {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
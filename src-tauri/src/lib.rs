@@ -4,23 +4,58 @@ mod geometry;
 mod mesh;
 pub mod types;
 
+use tauri::{Emitter, Manager};
+
+use engine::document::Documents;
+use types::event::{DocumentChanges, DOCUMENT_CHANGED_EVENT};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .setup(|app| {
+            // Forward engine changes to every window; each picks out its own document
+            let handle = app.handle().clone();
+            app.manage(Documents::with_listener(Box::new(
+                move |document_id, changes| {
+                    let payload = DocumentChanges {
+                        document_id: document_id.clone(),
+                        changes,
+                    };
+                    let _ = handle.emit(DOCUMENT_CHANGED_EVENT, payload);
+                },
+            )));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            // Documents
+            commands::document::open_document,
+            commands::document::close_document,
+            commands::document::copy_pieces,
             // Pattern CRUD
             commands::pattern::create_pattern_piece,
             commands::pattern::update_pattern_piece,
             commands::pattern::delete_pattern_piece,
             commands::pattern::get_all_pieces,
             commands::pattern::get_piece,
+            // Batched mutations
+            commands::batch::apply_batch,
+            // Seams
+            commands::seam::create_seam,
+            commands::seam::update_seam,
+            commands::seam::delete_seam,
+            commands::seam::get_all_seams,
+            commands::seam::validate_seams,
             // Geometry operations
             commands::geometry::compute_seam_allowance,
             commands::geometry::validate_piece_geometry,
             commands::geometry::compute_piece_area,
+            commands::geometry::measure_piece,
+            commands::geometry::match_seam_lengths,
+            commands::geometry::repair_piece,
+            commands::geometry::transform_piece,
             // 3D mesh generation
             commands::mesh::generate_3d_mesh,
             // Export
@@ -32,6 +67,19 @@ pub fn run() {
             commands::history::undo,
             commands::history::redo,
             commands::history::can_undo_redo,
+            commands::history::get_history,
+            commands::history::jump_to_history,
+            // Checkpoints
+            commands::checkpoint::create_checkpoint,
+            commands::checkpoint::list_checkpoints,
+            commands::checkpoint::delete_checkpoint,
+            commands::checkpoint::compare_checkpoint,
+            commands::checkpoint::restore_checkpoint,
+            // Embedded assets
+            commands::asset::import_asset,
+            commands::asset::list_assets,
+            commands::asset::get_asset_data,
+            commands::asset::remove_asset,
             // Project
             commands::project::save_project,
             commands::project::load_project,
@@ -39,6 +87,7 @@ pub fn run() {
             commands::project::save_recovery,
             commands::project::check_recovery,
             commands::project::clear_recovery,
+            commands::project::delete_unreadable_recovery,
             commands::project::restore_recovery,
         ])
         .run(tauri::generate_context!())
@@ -1,10 +1,13 @@
+use crate::engine::fold;
 use crate::geometry::tessellation;
+use crate::types::error::AppResult;
 use crate::types::mesh::MeshData;
 use crate::types::pattern::PatternPieceData;
 
 /// Generate a flat 3D mesh from 2D pattern pieces.
 /// Each piece is tessellated into triangles lying on the XY plane (z=0).
-pub fn generate_mesh(pieces: &[PatternPieceData]) -> Result<MeshData, String> {
+/// Cut-on-fold pieces are unfolded to their full shape first.
+pub fn generate_mesh(pieces: &[PatternPieceData]) -> AppResult<MeshData> {
     let mut all_positions: Vec<f32> = Vec::new();
     let mut all_normals: Vec<f32> = Vec::new();
     let mut all_indices: Vec<u32> = Vec::new();
@@ -17,6 +20,7 @@ pub fn generate_mesh(pieces: &[PatternPieceData]) -> Result<MeshData, String> {
             continue;
         }
 
+        let piece = fold::unfold(piece)?;
         let (positions, indices) = tessellation::tessellate_outline(&piece.origin, &piece.outline)?;
 
         let vertex_count = positions.len() / 3;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+pub type AssetId = String;
+
+/// What a file embedded in a project bundle is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssetKind {
+    FabricSwatch,
+    ReferencePhoto,
+    MeasurementTable,
+    ExportPreset,
+}
+
+/// Description of an embedded file, without its contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetInfo {
+    pub id: AssetId,
+    pub kind: AssetKind,
+    /// Original file name, e.g. "denim-swatch.jpg".
+    pub name: String,
+    pub media_type: String,
+    pub size_bytes: usize,
+}
+
+/// An embedded file and its contents.
+#[derive(Debug, Clone)]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub data: Vec<u8>,
+}
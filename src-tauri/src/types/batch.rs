@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::{PatternPieceData, PatternPieceId};
+use super::seam::{Seam, SeamId};
+use crate::geometry::transform::Transform2D;
+
+/// One mutation in a batch applied with `engine::batch::apply_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum BatchOp {
+    CreatePiece {
+        piece: PatternPieceData,
+    },
+    UpdatePiece {
+        id: PatternPieceId,
+        piece: PatternPieceData,
+    },
+    DeletePiece {
+        id: PatternPieceId,
+    },
+    TransformPiece {
+        id: PatternPieceId,
+        transform: Transform2D,
+    },
+    /// Create a transformed copy of a stored piece, named `name`. Its seams are not copied.
+    DuplicatePiece {
+        id: PatternPieceId,
+        name: String,
+        transform: Transform2D,
+    },
+    CreateSeam {
+        seam: Seam,
+    },
+    UpdateSeam {
+        id: SeamId,
+        seam: Seam,
+    },
+    DeleteSeam {
+        id: SeamId,
+    },
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::{PatternPieceData, PatternPieceId};
+use super::seam::Seam;
+
+pub type CheckpointId = String;
+
+/// A named copy of every piece and seam, saved inside the project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: CheckpointId,
+    pub name: String,
+    /// When the checkpoint was made, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub pieces: Vec<PatternPieceData>,
+    pub seams: Vec<Seam>,
+}
+
+/// A checkpoint as listed in the UI, without its contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    pub id: CheckpointId,
+    pub name: String,
+    pub timestamp_ms: u64,
+    pub piece_count: usize,
+    pub seam_count: usize,
+}
+
+/// A piece named in a checkpoint comparison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceRef {
+    pub id: PatternPieceId,
+    pub name: String,
+}
+
+/// What changed between a checkpoint and the current state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointDiff {
+    /// Pieces that exist now but not in the checkpoint.
+    pub added_pieces: Vec<PieceRef>,
+    /// Pieces in the checkpoint that have since been deleted.
+    pub removed_pieces: Vec<PieceRef>,
+    /// Pieces in both that have been edited, under their current names.
+    pub changed_pieces: Vec<PieceRef>,
+    pub added_seams: usize,
+    pub removed_seams: usize,
+    pub changed_seams: usize,
+}
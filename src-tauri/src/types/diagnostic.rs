@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::Point2D;
+use super::seam::SeamId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    /// The piece cannot be cut or exported reliably.
+    Error,
+    /// The piece is usable but probably not what the user intended.
+    Warning,
+}
+
+/// Stable, machine-readable identifier for each kind of validation finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticCode {
+    EmptyOutline,
+    MissingName,
+    NegativeSeamAllowance,
+    NonFiniteCoordinate,
+    OpenOutline,
+    DisconnectedSegment,
+    DegenerateSegment,
+    SelfIntersection,
+    InvalidFoldEdge,
+    InvalidSeam,
+    SeamLengthMismatch,
+}
+
+/// A single validation finding, optionally pinned to an outline segment, a point or a seam.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    /// Index into the piece's `outline` of the offending segment.
+    pub segment_index: Option<usize>,
+    /// Where to place a marker on the canvas, in piece coordinates.
+    pub location: Option<Point2D>,
+    /// The seam the finding is about, for seam validation.
+    pub seam_id: Option<SeamId>,
+}
+
+impl Diagnostic {
+    pub fn error(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    fn new(severity: Severity, code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            segment_index: None,
+            location: None,
+            seam_id: None,
+        }
+    }
+
+    pub fn at_segment(mut self, index: usize) -> Self {
+        self.segment_index = Some(index);
+        self
+    }
+
+    pub fn at_point(mut self, location: Point2D) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn for_seam(mut self, id: &SeamId) -> Self {
+        self.seam_id = Some(id.clone());
+        self
+    }
+}
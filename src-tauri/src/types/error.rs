@@ -1,21 +1,245 @@
+use std::path::Path;
+
 use serde::Serialize;
 
-#[derive(Debug, thiserror::Error, Serialize)]
+/// What an error refers to, where the engine knows it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ErrorContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub piece_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seam_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Index of an outline segment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment: Option<usize>,
+    /// Index of the failing operation within a batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<usize>,
+}
+
+/// An error from the engine. Commands return it as-is, serialized as
+/// `{ "code": "NotFound", "message": "...", "context": { ... } }`. The context is boxed to
+/// keep `AppResult` small.
+#[derive(Debug, Clone, thiserror::Error, Serialize)]
+#[serde(tag = "code")]
 pub enum AppError {
-    #[error("Not found: {0}")]
-    NotFound(String),
-    #[error("Invalid geometry: {0}")]
-    InvalidGeometry(String),
-    #[error("Export failed: {0}")]
-    ExportFailed(String),
-    #[error("Internal error: {0}")]
-    Internal(String),
+    /// A piece, seam, checkpoint, asset, document or file that doesn't exist.
+    #[error("{message}")]
+    NotFound {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    /// Geometry the engine can't work with, such as an empty or degenerate outline.
+    #[error("{message}")]
+    InvalidGeometry {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    /// An argument out of range, or a request that doesn't fit the current state.
+    #[error("{message}")]
+    InvalidInput {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    #[error("{message}")]
+    ExportFailed {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    /// Reading or writing a file failed.
+    #[error("{message}")]
+    Io {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    /// A project file that is corrupt or from a newer version.
+    #[error("{message}")]
+    InvalidProject {
+        message: String,
+        context: Box<ErrorContext>,
+    },
+    #[error("{message}")]
+    Internal {
+        message: String,
+        context: Box<ErrorContext>,
+    },
 }
 
-/// Tauri commands require `Result<T, String>` for error serialization across the IPC bridge.
-pub type AppResult<T> = Result<T, String>;
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn invalid_geometry(message: impl Into<String>) -> Self {
+        Self::InvalidGeometry {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn export_failed(message: impl Into<String>) -> Self {
+        Self::ExportFailed {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::Io {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn invalid_project(message: impl Into<String>) -> Self {
+        Self::InvalidProject {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+            context: Box::default(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.parts().0
+    }
+
+    pub fn context(&self) -> &ErrorContext {
+        self.parts().1
+    }
+
+    pub fn with_piece(mut self, id: &str) -> Self {
+        self.parts_mut().1.piece_id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_seam(mut self, id: &str) -> Self {
+        self.parts_mut().1.seam_id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.parts_mut().1.path = Some(path.as_ref().display().to_string());
+        self
+    }
+
+    pub fn with_segment(mut self, segment: usize) -> Self {
+        self.parts_mut().1.segment = Some(segment);
+        self
+    }
+
+    /// Mark the error as coming from operation `index` of a batch, saying so in its
+    /// message too.
+    pub fn in_operation(mut self, index: usize) -> Self {
+        let (message, context) = self.parts_mut();
+        *message = format!("Batch operation {index} failed: {message}");
+        context.operation = Some(index);
+        self
+    }
+
+    fn parts(&self) -> (&String, &ErrorContext) {
+        match self {
+            Self::NotFound { message, context }
+            | Self::InvalidGeometry { message, context }
+            | Self::InvalidInput { message, context }
+            | Self::ExportFailed { message, context }
+            | Self::Io { message, context }
+            | Self::InvalidProject { message, context }
+            | Self::Internal { message, context } => (message, &**context),
+        }
+    }
+
+    fn parts_mut(&mut self) -> (&mut String, &mut ErrorContext) {
+        match self {
+            Self::NotFound { message, context }
+            | Self::InvalidGeometry { message, context }
+            | Self::InvalidInput { message, context }
+            | Self::ExportFailed { message, context }
+            | Self::Io { message, context }
+            | Self::InvalidProject { message, context }
+            | Self::Internal { message, context } => (message, &mut **context),
+        }
+    }
+}
+
+/// The result of engine functions and Tauri commands; the error crosses the IPC bridge as
+/// structured JSON.
+pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_context() {
+        let error = AppError::invalid_geometry("Outline is not closed")
+            .with_piece("p1")
+            .with_segment(3);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "InvalidGeometry",
+                "message": "Outline is not closed",
+                "context": { "piece_id": "p1", "segment": 3 },
+            })
+        );
+        assert_eq!(error.to_string(), "Outline is not closed");
+    }
+
+    #[test]
+    fn empty_context_is_an_empty_object() {
+        let error = AppError::internal("Lock poisoned");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "code": "Internal", "message": "Lock poisoned", "context": {} })
+        );
+    }
+
+    #[test]
+    fn batch_operations_are_named_in_message_and_context() {
+        let error = AppError::not_found("Piece not found: p1")
+            .with_piece("p1")
+            .in_operation(2);
+        assert_eq!(
+            error.message(),
+            "Batch operation 2 failed: Piece not found: p1"
+        );
+        assert_eq!(
+            error.context(),
+            &ErrorContext {
+                piece_id: Some("p1".into()),
+                operation: Some(2),
+                ..Default::default()
+            }
+        );
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "NotFound");
+        assert_eq!(value["context"]["operation"], 2);
+    }
 
-/// Convert an `AppError` into a `String` for Tauri IPC.
-pub fn to_ipc_error(e: AppError) -> String {
-    e.to_string()
+    #[test]
+    fn paths_are_kept_as_text() {
+        let error = AppError::io("Write error").with_path(Path::new("/tmp/shirt.tcad"));
+        assert_eq!(error.context().path.as_deref(), Some("/tmp/shirt.tcad"));
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], "Io");
+    }
 }
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::PatternPieceId;
+use super::seam::SeamId;
+
+/// Name of the Tauri event that carries [`DocumentChanges`] to every window.
+pub const DOCUMENT_CHANGED_EVENT: &str = "document-changed";
+
+/// Something in a project that changed, so views showing it can refresh.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ChangeEvent {
+    PieceCreated {
+        id: PatternPieceId,
+    },
+    PieceUpdated {
+        id: PatternPieceId,
+    },
+    PieceDeleted {
+        id: PatternPieceId,
+    },
+    SeamCreated {
+        id: SeamId,
+    },
+    SeamUpdated {
+        id: SeamId,
+    },
+    SeamDeleted {
+        id: SeamId,
+    },
+    /// Undo steps were added, undone, redone or replaced.
+    HistoryChanged,
+    CheckpointsChanged,
+    AssetsChanged,
+    /// Everything was replaced, by loading, recovering or starting a new project.
+    ProjectLoaded,
+}
+
+/// The changes one command made to a document, in the order they happened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentChanges {
+    pub document_id: String,
+    pub changes: Vec<ChangeEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        let events = [
+            (
+                ChangeEvent::PieceUpdated { id: "p1".into() },
+                json!({ "type": "PieceUpdated", "id": "p1" }),
+            ),
+            (
+                ChangeEvent::SeamDeleted { id: "s1".into() },
+                json!({ "type": "SeamDeleted", "id": "s1" }),
+            ),
+            (
+                ChangeEvent::HistoryChanged,
+                json!({ "type": "HistoryChanged" }),
+            ),
+            (
+                ChangeEvent::ProjectLoaded,
+                json!({ "type": "ProjectLoaded" }),
+            ),
+        ];
+        for (event, expected) in events {
+            assert_eq!(serde_json::to_value(&event).unwrap(), expected);
+            assert_eq!(
+                serde_json::from_value::<ChangeEvent>(expected).unwrap(),
+                event
+            );
+        }
+    }
+
+    #[test]
+    fn changes_carry_their_document() {
+        let changes = DocumentChanges {
+            document_id: "doc".into(),
+            changes: vec![
+                ChangeEvent::PieceCreated { id: "p1".into() },
+                ChangeEvent::CheckpointsChanged,
+            ],
+        };
+        let value = serde_json::to_value(&changes).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "document_id": "doc",
+                "changes": [
+                    { "type": "PieceCreated", "id": "p1" },
+                    { "type": "CheckpointsChanged" },
+                ],
+            })
+        );
+        let back: DocumentChanges = serde_json::from_value(value).unwrap();
+        assert_eq!(back.changes, changes.changes);
+    }
+}
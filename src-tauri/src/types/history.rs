@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// One undo step, as listed in the history panel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// What the step did, e.g. "Move Front Bodice" or "Delete 3 pieces".
+    pub label: String,
+    /// When the step was made, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+
+/// The full undo list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryState {
+    /// Every step, oldest first: the ones that can be undone followed by the ones that can
+    /// be redone.
+    pub entries: Vec<HistoryEntry>,
+    /// How many of `entries` are applied to the current state.
+    pub position: usize,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Arc lengths (mm) along one path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathLength {
+    /// Length of each segment, in path order.
+    pub segments: Vec<f64>,
+    /// Length of the implicit straight edge from the last segment back to the start of a
+    /// closed path. Always `0` for open paths.
+    pub closing: f64,
+    pub total: f64,
+}
+
+/// Lengths of every path making up a piece.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceMeasurements {
+    pub perimeter: PathLength,
+    pub internal_lines: Vec<PathLength>,
+    pub cut_outs: Vec<PathLength>,
+}
@@ -1,3 +1,13 @@
+pub mod asset;
+pub mod batch;
+pub mod checkpoint;
+pub mod diagnostic;
 pub mod error;
+pub mod event;
+pub mod history;
+pub mod measurement;
 pub mod mesh;
 pub mod pattern;
+pub mod project;
+pub mod repair;
+pub mod seam;
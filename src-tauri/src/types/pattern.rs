@@ -8,34 +8,166 @@ pub struct Point2D {
     pub y: f64,
 }
 
+/// Per-edge attributes carried by every outline segment.
+/// Ignored on internal lines.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EdgeAttributes {
+    /// Seam allowance override for this edge. `None` uses the piece's `seam_allowance_mm`;
+    /// `0` marks an edge on the fold (no allowance).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seam_allowance_mm: Option<f64>,
+    /// Seam allowance corner treatment at the vertex where this edge ends.
+    /// `None` is a plain miter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corner: Option<CornerStyle>,
+}
+
+/// How the seam allowance is finished at a convex outline corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CornerStyle {
+    /// Extend both allowance lines until they meet.
+    #[default]
+    Miter,
+    /// Extend each allowance line by the width of the other and cut straight across,
+    /// for corners sharper than a right angle. Blunter corners are mitered.
+    Square,
+    /// Cut straight across between the ends of the two allowance lines.
+    Bevel,
+    /// Hem turn-up where the edge ending here is the hem: the next edge's allowance is
+    /// mirrored across the hem line so it lies flat once the hem is folded up.
+    HemIncoming,
+    /// Hem turn-up where the edge starting here is the hem: the previous edge's allowance
+    /// is mirrored across the hem line.
+    HemOutgoing,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum CurveSegment {
     Line {
         end: Point2D,
+        #[serde(flatten)]
+        edge: EdgeAttributes,
     },
     QuadraticBezier {
         control: Point2D,
         end: Point2D,
+        #[serde(flatten)]
+        edge: EdgeAttributes,
     },
     CubicBezier {
         control1: Point2D,
         control2: Point2D,
         end: Point2D,
+        #[serde(flatten)]
+        edge: EdgeAttributes,
     },
     Arc {
         center: Point2D,
         radius: f64,
         start_angle: f64,
         end_angle: f64,
+        #[serde(flatten)]
+        edge: EdgeAttributes,
     },
 }
 
+impl CurveSegment {
+    pub fn edge(&self) -> &EdgeAttributes {
+        match self {
+            CurveSegment::Line { edge, .. }
+            | CurveSegment::QuadraticBezier { edge, .. }
+            | CurveSegment::CubicBezier { edge, .. }
+            | CurveSegment::Arc { edge, .. } => edge,
+        }
+    }
+
+    pub fn edge_mut(&mut self) -> &mut EdgeAttributes {
+        match self {
+            CurveSegment::Line { edge, .. }
+            | CurveSegment::QuadraticBezier { edge, .. }
+            | CurveSegment::CubicBezier { edge, .. }
+            | CurveSegment::Arc { edge, .. } => edge,
+        }
+    }
+}
+
+/// A position on a piece outline: a segment index and the curve parameter within it.
+/// Index `outline.len()` refers to the implicit closing edge of an open outline.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OutlinePosition {
+    pub segment: usize,
+    pub t: f64,
+}
+
+/// Shape of a notch cut into the edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotchKind {
+    /// A single straight cut.
+    #[default]
+    Slit,
+    /// A straight cut with a crossbar at full depth.
+    T,
+    /// A wedge.
+    V,
+    /// A rectangular cut-out.
+    Castle,
+    /// A cut-out with a rounded end.
+    U,
+}
+
+/// A notch anchored to the outline, so it follows its edge when the outline is edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notch {
+    pub position: OutlinePosition,
+    pub kind: NotchKind,
+    pub depth_mm: f64,
+    /// Opening width of T, V, castle and U notches. Ignored for slits.
+    pub width_mm: f64,
+    /// Rotation (radians) away from the inward edge normal; `0` is perpendicular to the edge.
+    #[serde(default)]
+    pub angle: f64,
+}
+
+/// Kind of symbol marked inside a piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkingKind {
+    /// A drill hole, e.g. at a dart point or pocket corner.
+    DrillHole,
+    Button,
+    Buttonhole,
+    /// A line the piece is folded along.
+    FoldLine,
+    /// A "place on fold" bracket, with its arrows pointing at the fold.
+    PlaceOnFold,
+}
+
+/// An internal marking, in the same coordinates as the outline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Marking {
+    pub kind: MarkingKind,
+    /// Centre of drill holes and buttons; start of buttonholes, fold lines and brackets.
+    pub position: Point2D,
+    /// Diameter of drill holes and buttons; length of buttonholes, fold lines and brackets.
+    pub size_mm: f64,
+    /// Direction (radians) of buttonholes, fold lines and brackets. A bracket sits to the right
+    /// of this direction on screen, with its arrows pointing back at the fold through `position`.
+    #[serde(default)]
+    pub angle: f64,
+}
+
+/// A hole cut out of a piece, traced like the piece outline: the path starts at `origin`
+/// and is implicitly closed back to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CutOut {
+    pub origin: Point2D,
+    pub outline: Vec<CurveSegment>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PieceMetadata {
     pub fabric_type: Option<String>,
     pub cut_quantity: u32,
-    pub mirror: bool,
     pub notes: String,
 }
 
@@ -44,7 +176,6 @@ impl Default for PieceMetadata {
         Self {
             fabric_type: None,
             cut_quantity: 1,
-            mirror: false,
             notes: String::new(),
         }
     }
@@ -58,7 +189,15 @@ pub struct PatternPieceData {
     pub outline: Vec<CurveSegment>,
     pub grain_line: Option<(Point2D, Point2D)>,
     pub seam_allowance_mm: f64,
-    pub notches: Vec<Point2D>,
+    pub notches: Vec<Notch>,
     pub internal_lines: Vec<Vec<CurveSegment>>,
+    #[serde(default)]
+    pub cut_outs: Vec<CutOut>,
+    #[serde(default)]
+    pub markings: Vec<Marking>,
+    /// Straight outline edge the piece is cut on the fold along. The outline is then only
+    /// half of the piece; index `outline.len()` refers to the implicit closing edge.
+    #[serde(default)]
+    pub fold_edge: Option<usize>,
     pub metadata: PieceMetadata,
 }
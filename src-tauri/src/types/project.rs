@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// How a project is written to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectFormat {
+    /// A single JSON document, as written by earlier versions.
+    Json,
+    /// A zip holding a manifest, the project JSON and any embedded files.
+    Bundle,
+}
+
+/// A recovery snapshot saved during a previous session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoverySnapshot {
+    pub id: String,
+    /// When the snapshot was saved, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub piece_count: usize,
+}
+
+/// What was left behind by a previous session that didn't close cleanly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryStatus {
+    /// Snapshots that can be restored, newest first.
+    pub snapshots: Vec<RecoverySnapshot>,
+    /// Why any snapshots couldn't be read, each saying where the file was kept.
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::{PatternPieceData, Point2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepairKind {
+    ClosedGap,
+    RemovedDegenerateSegment,
+    MergedCollinearLines,
+    RemovedLoop,
+    ReversedWinding,
+    ClearedFoldEdge,
+}
+
+/// One change made by the repair pass, located where it happened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairChange {
+    pub kind: RepairKind,
+    pub message: String,
+    pub location: Option<Point2D>,
+}
+
+/// The repaired piece and everything that was changed to produce it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub piece: PatternPieceData,
+    pub changes: Vec<RepairChange>,
+}
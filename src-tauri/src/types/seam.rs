@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use super::pattern::{OutlinePosition, PatternPieceId, Point2D};
+
+/// A stretch of a piece outline from `start` to `end`, following the outline direction and
+/// wrapping past the origin when `end` comes before `start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineSection {
+    pub piece_id: PatternPieceId,
+    pub start: OutlinePosition,
+    pub end: OutlinePosition,
+    /// Walk the section from `end` back to `start`.
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+pub type SeamId = String;
+
+/// Construction of the seam.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeamType {
+    #[default]
+    Plain,
+    French,
+    FlatFelled,
+    Overlocked,
+    Bound,
+}
+
+/// Two outline sections, usually on different pieces, that are sewn together. They are
+/// walked side by side, each in its own walking direction, so the point where the walk of
+/// section A begins meets the point where the walk of section B begins. There is no
+/// separate seam direction: sections sewn end to start set `reversed` on section B.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Seam {
+    pub id: SeamId,
+    pub a: OutlineSection,
+    pub b: OutlineSection,
+    pub seam_type: SeamType,
+    /// Intended extra length (mm) of section A over section B, eased in while sewing.
+    pub ease_mm: f64,
+}
+
+/// Where a notch falls on one outline section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotchPlacement {
+    pub position: OutlinePosition,
+    pub point: Point2D,
+}
+
+/// Notches on the two sections that meet when they are sewn together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotchPair {
+    pub a: NotchPlacement,
+    pub b: NotchPlacement,
+}
+
+/// Result of walking two outline sections against each other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeamLengthMatch {
+    pub length_a: f64,
+    pub length_b: f64,
+    /// `length_a - length_b` (mm).
+    pub difference: f64,
+    /// `difference` as a percentage of `length_b`: the ease worked into section A.
+    pub ease_percent: f64,
+    pub notches: Vec<NotchPair>,
+}
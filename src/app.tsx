@@ -1,6 +1,6 @@
-import type { PatternPieceData } from "@/types/pattern";
+import type { RecoverySnapshot } from "@/types/project";
 
-import { QueryClientProvider, useQueryClient } from "@tanstack/react-query";
+import { QueryClientProvider } from "@tanstack/react-query";
 import { useEffect, useState } from "react";
 
 import { CenterWorkspace } from "@/components/layout/center-workspace";
@@ -12,20 +12,24 @@ import { ThemeProvider } from "@/components/providers/theme-provider";
 import { SidebarInset, SidebarProvider } from "@/components/ui/sidebar";
 import { TooltipProvider } from "@/components/ui/tooltip";
 import { UpdateDialog } from "@/components/update-dialog";
-import { RecoveryDialog } from "@/features/pattern/components/recovery-dialog";
-import { checkRecovery, clearRecovery, restoreRecovery, saveRecovery } from "@/lib/invoke";
+import { RecoveryDialog, UnreadableRecoveryDialog } from "@/features/pattern/components/recovery-dialog";
+import { useDocumentChanges } from "@/features/pattern/hooks/use-document-changes";
+import { checkRecovery, clearRecovery, deleteUnreadableRecovery, restoreRecovery, saveRecovery } from "@/lib/invoke";
 import { queryClient } from "@/lib/query-client";
-import { patternKeys } from "@/lib/query-keys";
 import { useProjectStore } from "@/stores/project-store";
 
 function AppInner() {
-  const qc = useQueryClient();
-  const [recoveryPieces, setRecoveryPieces] = useState<PatternPieceData[] | null>(null);
+  useDocumentChanges();
+  const [recoverySnapshots, setRecoverySnapshots] = useState<RecoverySnapshot[] | null>(null);
+  const [recoveryError, setRecoveryError] = useState<string | null>(null);
 
   useEffect(() => {
-    checkRecovery().then((pieces) => {
-      if (pieces && pieces.length > 0) {
-        setRecoveryPieces(pieces);
+    checkRecovery().then((recovery) => {
+      if (recovery.snapshots.length > 0) {
+        setRecoverySnapshots(recovery.snapshots);
+      }
+      if (recovery.errors.length > 0) {
+        setRecoveryError(recovery.errors.join("\n"));
       }
     });
   }, []);
@@ -53,18 +57,26 @@ function AppInner() {
           <PropertiesSidebar />
         </SidebarProvider>
       </TooltipProvider>
-      {recoveryPieces && (
+      {recoverySnapshots && (
         <RecoveryDialog
-          pieces={recoveryPieces}
-          onRecover={() => {
-            restoreRecovery().then(() => {
-              qc.invalidateQueries({ queryKey: patternKeys.all });
-            });
-            setRecoveryPieces(null);
+          snapshots={recoverySnapshots}
+          onRecover={(id) => {
+            void restoreRecovery(id);
+            setRecoverySnapshots(null);
           }}
           onDiscard={() => {
             clearRecovery();
-            setRecoveryPieces(null);
+            setRecoverySnapshots(null);
+          }}
+        />
+      )}
+      {recoveryError && !recoverySnapshots && (
+        <UnreadableRecoveryDialog
+          message={recoveryError}
+          onKeep={() => setRecoveryError(null)}
+          onDelete={() => {
+            void deleteUnreadableRecovery();
+            setRecoveryError(null);
           }}
         />
       )}
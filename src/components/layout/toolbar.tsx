@@ -4,6 +4,7 @@ import { Download04Icon, KeyboardIcon, RedoIcon, UndoIcon } from "@hugeicons/cor
 import { HugeiconsIcon } from "@hugeicons/react";
 import { save } from "@tauri-apps/plugin-dialog";
 
+import { SaveSettings } from "@/components/save-settings";
 import { ThemeToggle } from "@/components/theme-toggle";
 
 import { Button } from "@/components/ui/button";
@@ -15,6 +16,7 @@ import { useCanUndoRedo, usePatternPieces, useRedo, useUndo } from "@/features/p
 import { useWorkspace } from "@/hooks/use-workspace";
 import { exportDxf, exportPdf, exportSvgToFile } from "@/lib/invoke";
 import { useProjectStore } from "@/stores/project-store";
+import { errorMessage } from "@/types/error";
 
 const viewModes: { id: ViewMode; label: string }[] = [
   { id: "2d", label: "2D" },
@@ -172,7 +174,7 @@ export function Toolbar() {
                         promise = exportDxf(ids, path);
                       else if (ext === "pdf")
                         promise = exportPdf(ids, path);
-                      promise?.catch(err => console.error(`Export ${format} failed:`, err));
+                      promise?.catch(err => console.error(`Export ${format} failed: ${errorMessage(err)}`, err));
                     });
                   }}
                 >
@@ -218,6 +220,7 @@ export function Toolbar() {
             ))}
           </PopoverContent>
         </Popover>
+        <SaveSettings />
         <ThemeToggle />
       </div>
     </header>
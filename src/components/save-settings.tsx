@@ -0,0 +1,62 @@
+import { Settings01Icon } from "@hugeicons/core-free-icons";
+import { HugeiconsIcon } from "@hugeicons/react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Popover, PopoverContent, PopoverTrigger } from "@/components/ui/popover";
+import { useProjectStore } from "@/stores/project-store";
+
+// A whole number of at least 0 from a number input, or null while it's being cleared
+function countFrom(input: HTMLInputElement): number | null {
+  return Number.isNaN(input.valueAsNumber) ? null : Math.max(0, Math.round(input.valueAsNumber));
+}
+
+export function SaveSettings() {
+  const { historyDepth, setHistoryDepth, backupCount, setBackupCount } = useProjectStore();
+
+  return (
+    <Popover>
+      <PopoverTrigger asChild>
+        <Button variant="ghost" size="icon" className="size-8" title="Save settings">
+          <HugeiconsIcon icon={Settings01Icon} size={18} strokeWidth={2} />
+        </Button>
+      </PopoverTrigger>
+      <PopoverContent align="end" className="w-56 space-y-2 p-3">
+        <div className="space-y-1">
+          <Label htmlFor="save-history-depth" className="text-muted-foreground">Undo steps saved with project</Label>
+          <Input
+            id="save-history-depth"
+            type="number"
+            min={0}
+            step={1}
+            value={historyDepth}
+            onChange={(e) => {
+              const depth = countFrom(e.target);
+              if (depth !== null)
+                setHistoryDepth(depth);
+            }}
+            className="h-7 text-xs"
+          />
+          <p className="text-[10px] text-muted-foreground">0 saves none. Recovery snapshots always keep recent steps.</p>
+        </div>
+        <div className="space-y-1">
+          <Label htmlFor="save-backup-count" className="text-muted-foreground">Backups kept</Label>
+          <Input
+            id="save-backup-count"
+            type="number"
+            min={0}
+            step={1}
+            value={backupCount}
+            onChange={(e) => {
+              const count = countFrom(e.target);
+              if (count !== null)
+                setBackupCount(count);
+            }}
+            className="h-7 text-xs"
+          />
+        </div>
+      </PopoverContent>
+    </Popover>
+  );
+}
@@ -0,0 +1,14 @@
+import { Button } from "@/components/ui/button";
+
+// Shown in place of the app when the window couldn't open its document
+export function StartupError({ message }: { message: string }) {
+  return (
+    <div className="flex h-screen flex-col items-center justify-center gap-3 p-6 text-center text-sm">
+      <p className="font-medium">This window couldn't open a document.</p>
+      <p className="max-w-md text-muted-foreground">{message}</p>
+      <Button variant="outline" size="sm" onClick={() => window.location.reload()}>
+        Try again
+      </Button>
+    </div>
+  );
+}
@@ -4,7 +4,7 @@ import type { Tool } from "@/stores/tool-store";
 
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { useCallback, useEffect, useRef } from "react";
-import { useCreatePiece, useDeletePiece, useLoadProject, useNewProject, usePatternPieces, useRedo, useSaveProject, useUndo, useUpdatePiece } from "@/features/pattern/hooks/use-pattern-queries";
+import { useApplyBatch, useCreatePiece, useDeletePiece, useLoadProject, useNewProject, usePatternPieces, useRedo, useSaveProject, useUndo, useUpdatePiece } from "@/features/pattern/hooks/use-pattern-queries";
 import { useProjectStore } from "@/stores/project-store";
 import { useSelectionStore } from "@/stores/selection-store";
 import { useToolStore } from "@/stores/tool-store";
@@ -80,6 +80,7 @@ export function Canvas2D() {
     createPiece: useCreatePiece(),
     updatePiece: useUpdatePiece(),
     deletePiece: useDeletePiece(),
+    applyBatch: useApplyBatch(),
     undo: useUndo(),
     redo: useRedo(),
     save: useSaveProject(),
@@ -131,6 +132,7 @@ export function Canvas2D() {
       createPiece: piece => mutRef.current.createPiece.mutate(piece),
       updatePiece: (id, piece) => mutRef.current.updatePiece.mutate({ id, piece }),
       deletePiece: id => mutRef.current.deletePiece.mutate(id),
+      applyBatch: (ops, label) => mutRef.current.applyBatch.mutateAsync({ ops, label }),
       selectPiece: id => useSelectionStore.getState().select(id),
       togglePiece: id => useSelectionStore.getState().toggle(id),
       clearSelection: () => useSelectionStore.getState().clear(),
@@ -1,5 +1,5 @@
 import type { Camera } from "./canvas-math";
-import type { CurveSegment, PatternPieceData, PatternPieceId, Point2D } from "@/types/pattern";
+import type { CurveSegment, Marking, Notch, PatternPieceData, PatternPieceId, Point2D } from "@/types/pattern";
 
 import { applyTransform, displayY, formatValue, getGridStep } from "./canvas-math";
 
@@ -142,16 +142,103 @@ function drawGrainLine(ctx: CanvasRenderingContext2D, piece: PatternPieceData, z
   ctx.stroke();
 }
 
+function segmentEnd(seg: CurveSegment): Point2D {
+  if (seg.type === "Arc") {
+    return {
+      x: seg.center.x + seg.radius * Math.cos(seg.end_angle),
+      y: seg.center.y + seg.radius * Math.sin(seg.end_angle),
+    };
+  }
+  return seg.end;
+}
+
+function pointOnSegment(start: Point2D, seg: CurveSegment, t: number): Point2D {
+  const u = 1 - t;
+  switch (seg.type) {
+    case "Line":
+      return { x: u * start.x + t * seg.end.x, y: u * start.y + t * seg.end.y };
+    case "QuadraticBezier":
+      return {
+        x: u * u * start.x + 2 * u * t * seg.control.x + t * t * seg.end.x,
+        y: u * u * start.y + 2 * u * t * seg.control.y + t * t * seg.end.y,
+      };
+    case "CubicBezier":
+      return {
+        x: u * u * u * start.x + 3 * u * u * t * seg.control1.x + 3 * u * t * t * seg.control2.x + t * t * t * seg.end.x,
+        y: u * u * u * start.y + 3 * u * u * t * seg.control1.y + 3 * u * t * t * seg.control2.y + t * t * t * seg.end.y,
+      };
+    case "Arc": {
+      const a = seg.start_angle + t * (seg.end_angle - seg.start_angle);
+      return { x: seg.center.x + seg.radius * Math.cos(a), y: seg.center.y + seg.radius * Math.sin(a) };
+    }
+  }
+}
+
+// Notches are anchored to a segment; index outline.length is the implicit closing edge.
+function notchPoint(piece: PatternPieceData, notch: Notch): Point2D | null {
+  const { segment, t } = notch.position;
+  if (segment > piece.outline.length)
+    return null;
+  const start = segment > 0 ? segmentEnd(piece.outline[segment - 1]) : piece.origin;
+  const seg: CurveSegment = piece.outline[segment] ?? { type: "Line", end: piece.origin };
+  return pointOnSegment(start, seg, t);
+}
+
 function drawNotches(ctx: CanvasRenderingContext2D, piece: PatternPieceData, zoom: number) {
   const size = 4 / zoom;
   ctx.fillStyle = COLOR_NOTCH;
-  for (const n of piece.notches) {
+  for (const notch of piece.notches) {
+    const n = notchPoint(piece, notch);
+    if (!n)
+      continue;
     ctx.beginPath();
     ctx.arc(n.x, n.y, size, 0, Math.PI * 2);
     ctx.fill();
   }
 }
 
+function drawMarking(ctx: CanvasRenderingContext2D, marking: Marking) {
+  const { x, y } = marking.position;
+  const angle = marking.angle ?? 0;
+  const ex = x + marking.size_mm * Math.cos(angle);
+  const ey = y + marking.size_mm * Math.sin(angle);
+
+  ctx.beginPath();
+  switch (marking.kind) {
+    case "DrillHole":
+    case "Button":
+      ctx.arc(x, y, marking.size_mm / 2, 0, Math.PI * 2);
+      break;
+    case "Buttonhole":
+    case "FoldLine":
+      ctx.moveTo(x, y);
+      ctx.lineTo(ex, ey);
+      break;
+    case "PlaceOnFold": {
+      // Bracket to the right of the direction, legs reaching back to the fold
+      const depth = 15;
+      const nx = -Math.sin(angle) * depth;
+      const ny = Math.cos(angle) * depth;
+      ctx.moveTo(x, y);
+      ctx.lineTo(x + nx, y + ny);
+      ctx.lineTo(ex + nx, ey + ny);
+      ctx.lineTo(ex, ey);
+      break;
+    }
+  }
+  ctx.stroke();
+}
+
+function drawMarkings(ctx: CanvasRenderingContext2D, piece: PatternPieceData, zoom: number) {
+  ctx.strokeStyle = COLOR_GRAIN;
+  ctx.lineWidth = 1 / zoom;
+  for (const marking of piece.markings) {
+    ctx.setLineDash(marking.kind === "FoldLine" ? [4 / zoom, 2 / zoom] : []);
+    drawMarking(ctx, marking);
+  }
+  ctx.setLineDash([]);
+}
+
 function drawPieceName(ctx: CanvasRenderingContext2D, piece: PatternPieceData, zoom: number) {
   // Find center of bounding box
   let cx = piece.origin.x;
@@ -203,6 +290,7 @@ export function drawPieces(
     drawPieceOutline(ctx, piece, color, lineWidth);
     drawGrainLine(ctx, piece, camera.zoom);
     drawNotches(ctx, piece, camera.zoom);
+    drawMarkings(ctx, piece, camera.zoom);
     drawPieceName(ctx, piece, camera.zoom);
   }
 
@@ -1,52 +1,28 @@
 import type { Camera } from "../canvas-math";
 import type { CanvasTool, PointerState, ToolContext } from "./tool-types";
-import type { CurveSegment, PatternPieceData, Point2D } from "@/types/pattern";
+import type { BatchOp } from "@/types/batch";
+import type { PatternPieceData, Point2D } from "@/types/pattern";
+import type { Transform2D } from "@/types/transform";
 
-import { applyTransform } from "../canvas-math";
-import { hitTestPieces } from "../hit-test";
-import { duplicatePiece, mirrorPieceX } from "../piece-factory";
+import { compose, mirrorXAbout, translate } from "@/features/pattern/lib/transforms";
 
-function offsetSegment(seg: CurveSegment, dx: number, dy: number): CurveSegment {
-  switch (seg.type) {
-    case "Line":
-      return { ...seg, end: { x: seg.end.x + dx, y: seg.end.y + dy } };
-    case "QuadraticBezier":
-      return {
-        ...seg,
-        control: { x: seg.control.x + dx, y: seg.control.y + dy },
-        end: { x: seg.end.x + dx, y: seg.end.y + dy },
-      };
-    case "CubicBezier":
-      return {
-        ...seg,
-        control1: { x: seg.control1.x + dx, y: seg.control1.y + dy },
-        control2: { x: seg.control2.x + dx, y: seg.control2.y + dy },
-        end: { x: seg.end.x + dx, y: seg.end.y + dy },
-      };
-    case "Arc":
-      return {
-        ...seg,
-        center: { x: seg.center.x + dx, y: seg.center.y + dy },
-      };
-  }
+import { applyTransform } from "../canvas-math";
+import { getBounds, hitTestPieces } from "../hit-test";
+import { nextPieceName } from "../piece-factory";
+
+// Where a drag leaves a piece: moved by (dx, dy), mirrored about its own centre first
+// when mirror-duplicating
+function dragTransform(piece: PatternPieceData, dx: number, dy: number, mirror: boolean): Transform2D {
+  const move = translate(dx, dy);
+  if (!mirror)
+    return move;
+  const { minX, maxX } = getBounds(piece);
+  return compose(mirrorXAbout((minX + maxX) / 2), move);
 }
 
-function offsetPiece(piece: PatternPieceData, dx: number, dy: number): PatternPieceData {
-  return {
-    ...piece,
-    origin: { x: piece.origin.x + dx, y: piece.origin.y + dy },
-    outline: piece.outline.map(seg => offsetSegment(seg, dx, dy)),
-    grain_line: piece.grain_line
-      ? [
-          { x: piece.grain_line[0].x + dx, y: piece.grain_line[0].y + dy },
-          { x: piece.grain_line[1].x + dx, y: piece.grain_line[1].y + dy },
-        ]
-      : null,
-    notches: piece.notches.map(n => ({ x: n.x + dx, y: n.y + dy })),
-    internal_lines: piece.internal_lines.map(line =>
-      line.map(seg => offsetSegment(seg, dx, dy)),
-    ),
-  };
+// History label for an action on pieces, e.g. "Move Front Bodice" or "Move 3 pieces"
+function actionLabel(verb: string, pieces: PatternPieceData[]): string {
+  return pieces.length === 1 ? `${verb} ${pieces[0].name}` : `${verb} ${pieces.length} pieces`;
 }
 
 export function createSelectTool(ctx: ToolContext): CanvasTool {
@@ -122,32 +98,28 @@ export function createSelectTool(ctx: ToolContext): CanvasTool {
       const dy = dragOffset.y;
 
       if (isDuplicating) {
-        const newIds: string[] = [];
-        for (const id of dragIds) {
-          const piece = ctx.piecesRef.current.find(p => p.id === id);
-          if (!piece)
-            continue;
-          const base = isMirroring ? mirrorPieceX(piece) : piece;
-          const clone = duplicatePiece(base);
-          const moved = offsetPiece(clone, dx, dy);
-          ctx.createPiece(moved);
-          newIds.push(moved.id);
-        }
-        // Select the new clones
-        if (newIds.length > 0) {
-          ctx.selectPiece(newIds[0]);
-          for (let i = 1; i < newIds.length; i++) {
-            ctx.togglePiece(newIds[i]);
+        const pieces = ctx.piecesRef.current.filter(p => dragIds.has(p.id));
+        const ops = pieces.map((piece): BatchOp => ({
+          type: "DuplicatePiece",
+          id: piece.id,
+          name: nextPieceName(),
+          transform: dragTransform(piece, dx, dy, isMirroring),
+        }));
+        // Select the new clones once the engine has assigned their ids
+        void ctx.applyBatch(ops, actionLabel("Duplicate", pieces)).then((ids) => {
+          const newIds = ids.filter((id): id is string => id !== null);
+          if (newIds.length > 0) {
+            ctx.selectPiece(newIds[0]);
+            for (let i = 1; i < newIds.length; i++) {
+              ctx.togglePiece(newIds[i]);
+            }
           }
-        }
+        });
       }
       else {
-        for (const id of dragIds) {
-          const piece = ctx.piecesRef.current.find(p => p.id === id);
-          if (piece) {
-            ctx.updatePiece(piece.id, offsetPiece(piece, dx, dy));
-          }
-        }
+        const pieces = ctx.piecesRef.current.filter(p => dragIds.has(p.id));
+        const ops = pieces.map((p): BatchOp => ({ type: "TransformPiece", id: p.id, transform: translate(dx, dy) }));
+        void ctx.applyBatch(ops, actionLabel("Move", pieces));
       }
     }
 
@@ -166,9 +138,7 @@ export function createSelectTool(ctx: ToolContext): CanvasTool {
       if (ids.size === 0)
         return;
       e.preventDefault();
-      for (const id of ids) {
-        ctx.deletePiece(id);
-      }
+      void ctx.applyBatch([...ids].map((id): BatchOp => ({ type: "DeletePiece", id })));
       ctx.clearSelection();
     }
   }
@@ -191,41 +161,38 @@ export function createSelectTool(ctx: ToolContext): CanvasTool {
       if (!piece)
         continue;
 
-      // Use mirrored shape for mirror-duplicate preview
-      const ghost = isMirroring ? mirrorPieceX(piece) : piece;
-
+      // Draw the piece where the engine will put it; moves and mirrors keep the line width
+      const t = dragTransform(piece, dx, dy, isMirroring);
+      drawCtx.save();
+      drawCtx.transform(t.a, t.c, t.b, t.d, t.tx, t.ty);
       drawCtx.beginPath();
-      drawCtx.moveTo(ghost.origin.x + dx, ghost.origin.y + dy);
+      drawCtx.moveTo(piece.origin.x, piece.origin.y);
 
-      for (const seg of ghost.outline) {
+      for (const seg of piece.outline) {
         switch (seg.type) {
           case "Line":
-            drawCtx.lineTo(seg.end.x + dx, seg.end.y + dy);
+            drawCtx.lineTo(seg.end.x, seg.end.y);
             break;
           case "QuadraticBezier":
-            drawCtx.quadraticCurveTo(
-              seg.control.x + dx,
-              seg.control.y + dy,
-              seg.end.x + dx,
-              seg.end.y + dy,
-            );
+            drawCtx.quadraticCurveTo(seg.control.x, seg.control.y, seg.end.x, seg.end.y);
             break;
           case "CubicBezier":
             drawCtx.bezierCurveTo(
-              seg.control1.x + dx,
-              seg.control1.y + dy,
-              seg.control2.x + dx,
-              seg.control2.y + dy,
-              seg.end.x + dx,
-              seg.end.y + dy,
+              seg.control1.x,
+              seg.control1.y,
+              seg.control2.x,
+              seg.control2.y,
+              seg.end.x,
+              seg.end.y,
             );
             break;
           case "Arc":
-            drawCtx.arc(seg.center.x + dx, seg.center.y + dy, seg.radius, seg.start_angle, seg.end_angle);
+            drawCtx.arc(seg.center.x, seg.center.y, seg.radius, seg.start_angle, seg.end_angle);
             break;
         }
       }
       drawCtx.stroke();
+      drawCtx.restore();
     }
 
     drawCtx.setLineDash([]);
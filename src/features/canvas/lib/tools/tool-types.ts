@@ -1,4 +1,5 @@
 import type { Camera } from "../canvas-math";
+import type { BatchOp } from "@/types/batch";
 import type { PatternPieceData, PatternPieceId, Point2D } from "@/types/pattern";
 
 export type ToolContext = {
@@ -12,6 +13,9 @@ export type ToolContext = {
   createPiece: (piece: PatternPieceData) => void;
   updatePiece: (id: PatternPieceId, piece: PatternPieceData) => void;
   deletePiece: (id: PatternPieceId) => void;
+  // One undo step for the whole list, labelled `label` if given; resolves to the ids
+  // assigned by create operations
+  applyBatch: (ops: BatchOp[], label?: string) => Promise<(string | null)[]>;
   selectPiece: (id: PatternPieceId) => void;
   togglePiece: (id: PatternPieceId) => void;
   clearSelection: () => void;
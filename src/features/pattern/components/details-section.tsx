@@ -11,6 +11,7 @@ export function DetailsSection({ piece }: { piece: PatternPieceData }) {
         <ReadOnlyRow label="Notches" value={String(piece.notches.length)} />
         <ReadOnlyRow label="Grain line" value={piece.grain_line ? "Yes" : "No"} />
         <ReadOnlyRow label="Internal lines" value={String(piece.internal_lines.length)} />
+        <ReadOnlyRow label="Markings" value={String(piece.markings.length)} />
       </div>
     </div>
   );
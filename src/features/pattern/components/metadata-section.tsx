@@ -1,23 +1,23 @@
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { SidebarGroupLabel } from "@/components/ui/sidebar";
-import { Switch } from "@/components/ui/switch";
 import { Textarea } from "@/components/ui/textarea";
 
 type MetadataSectionProps = {
   fabricType: string | null;
   cutQuantity: number;
-  mirror: boolean;
+  foldEdge: number | null;
   notes: string;
   onFabricChange: (value: string | null) => void;
   onCutQtyChange: (value: number) => void;
-  onMirrorChange: (value: boolean) => void;
+  onFoldEdgeChange: (value: number | null) => void;
   onNotesChange: (value: string) => void;
   onBlur: () => void;
   cutQtyError?: string;
+  foldEdgeError?: string;
 };
 
-export function MetadataSection({ fabricType, cutQuantity, mirror, notes, onFabricChange, onCutQtyChange, onMirrorChange, onNotesChange, onBlur, cutQtyError }: MetadataSectionProps) {
+export function MetadataSection({ fabricType, cutQuantity, foldEdge, notes, onFabricChange, onCutQtyChange, onFoldEdgeChange, onNotesChange, onBlur, cutQtyError, foldEdgeError }: MetadataSectionProps) {
   return (
     <div>
       <SidebarGroupLabel>Metadata</SidebarGroupLabel>
@@ -47,13 +47,20 @@ export function MetadataSection({ fabricType, cutQuantity, mirror, notes, onFabr
           />
           {cutQtyError && <p className="text-[10px] text-destructive">{cutQtyError}</p>}
         </div>
-        <div className="flex items-center justify-between py-0.5">
-          <Label htmlFor="piece-mirror" className="text-muted-foreground">Mirror</Label>
-          <Switch
-            id="piece-mirror"
-            checked={mirror}
-            onCheckedChange={onMirrorChange}
+        <div className="space-y-1">
+          <Label htmlFor="piece-fold-edge" className="text-muted-foreground">Cut on fold (edge)</Label>
+          <Input
+            id="piece-fold-edge"
+            type="number"
+            min={0}
+            step={1}
+            value={foldEdge ?? ""}
+            onChange={e => onFoldEdgeChange(Number.isNaN(e.target.valueAsNumber) ? null : Math.max(0, Math.round(e.target.valueAsNumber)))}
+            onBlur={onBlur}
+            placeholder="None"
+            className="h-7 text-xs"
           />
+          {foldEdgeError && <p className="text-[10px] text-destructive">{foldEdgeError}</p>}
         </div>
         <div className="space-y-1">
           <Label htmlFor="piece-notes" className="text-muted-foreground">Notes</Label>
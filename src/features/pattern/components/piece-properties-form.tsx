@@ -2,7 +2,6 @@ import type { PatternPieceData } from "@/types/pattern";
 
 import { useCallback, useState } from "react";
 
-import { mirrorPieceX } from "@/features/canvas/lib/piece-factory";
 import { useUpdatePiece } from "@/features/pattern/hooks/use-pattern-queries";
 
 import { extractProperties, piecePropertiesSchema } from "../lib/piece-schema";
@@ -34,28 +33,17 @@ function PiecePropertiesFormInner({ piece }: { piece: PatternPieceData }) {
       ...piece,
       name: result.data.name,
       seam_allowance_mm: result.data.seam_allowance_mm,
+      fold_edge: result.data.fold_edge,
       metadata: {
         ...piece.metadata,
         fabric_type: result.data.fabric_type,
         cut_quantity: result.data.cut_quantity,
-        mirror: result.data.mirror,
         notes: result.data.notes,
       },
     };
     updatePiece.mutate({ id: piece.id, piece: updated });
   }, [values, piece, updatePiece]);
 
-  const handleMirrorChange = useCallback((mirror: boolean) => {
-    setValues(prev => ({ ...prev, mirror }));
-    // Mirror the geometry and toggle the flag
-    const mirrored = mirrorPieceX(piece);
-    const updated: PatternPieceData = {
-      ...mirrored,
-      metadata: { ...piece.metadata, mirror },
-    };
-    updatePiece.mutate({ id: piece.id, piece: updated });
-  }, [piece, updatePiece]);
-
   return (
     <div className="space-y-3 text-xs">
       <GeneralSection
@@ -71,14 +59,15 @@ function PiecePropertiesFormInner({ piece }: { piece: PatternPieceData }) {
       <MetadataSection
         fabricType={values.fabric_type}
         cutQuantity={values.cut_quantity}
-        mirror={values.mirror}
+        foldEdge={values.fold_edge}
         notes={values.notes}
         onFabricChange={fabric_type => setValues(prev => ({ ...prev, fabric_type }))}
         onCutQtyChange={cut_quantity => setValues(prev => ({ ...prev, cut_quantity }))}
-        onMirrorChange={handleMirrorChange}
+        onFoldEdgeChange={fold_edge => setValues(prev => ({ ...prev, fold_edge }))}
         onNotesChange={notes => setValues(prev => ({ ...prev, notes }))}
         onBlur={save}
         cutQtyError={errors.cut_quantity}
+        foldEdgeError={errors.fold_edge}
       />
       <DetailsSection piece={piece} />
     </div>
@@ -1,4 +1,6 @@
-import type { PatternPieceData } from "@/types/pattern";
+import type { RecoverySnapshot } from "@/types/project";
+
+import { useState } from "react";
 
 import {
   AlertDialog,
@@ -10,36 +12,77 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "@/components/ui/alert-dialog";
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+
+function snapshotLabel(snapshot: RecoverySnapshot): string {
+  const when = new Date(snapshot.timestamp_ms).toLocaleString();
+  const pieces = snapshot.piece_count === 1 ? "piece" : "pieces";
+  return `${when} (${snapshot.piece_count} ${pieces})`;
+}
 
 export function RecoveryDialog({
-  pieces,
+  snapshots,
   onRecover,
   onDiscard,
 }: {
-  pieces: PatternPieceData[];
-  onRecover: () => void;
+  snapshots: RecoverySnapshot[];
+  onRecover: (id: string) => void;
   onDiscard: () => void;
 }) {
+  const [selectedId, setSelectedId] = useState(snapshots[0].id);
+
   return (
     <AlertDialog defaultOpen>
       <AlertDialogContent>
         <AlertDialogHeader>
           <AlertDialogTitle>Recover unsaved work?</AlertDialogTitle>
           <AlertDialogDescription>
-            Found
-            {" "}
-            {pieces.length}
-            {" "}
-            unsaved
-            {" "}
-            {pieces.length === 1 ? "piece" : "pieces"}
-            {" "}
-            from a previous session.
+            Found unsaved work from a previous session. Choose which snapshot to recover.
           </AlertDialogDescription>
         </AlertDialogHeader>
+        <Select value={selectedId} onValueChange={setSelectedId}>
+          <SelectTrigger className="w-full">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {snapshots.map(snapshot => (
+              <SelectItem key={snapshot.id} value={snapshot.id}>
+                {snapshotLabel(snapshot)}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
         <AlertDialogFooter>
           <AlertDialogCancel onClick={onDiscard}>Discard</AlertDialogCancel>
-          <AlertDialogAction onClick={onRecover}>Recover</AlertDialogAction>
+          <AlertDialogAction onClick={() => onRecover(selectedId)}>Recover</AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
+
+export function UnreadableRecoveryDialog({
+  message,
+  onKeep,
+  onDelete,
+}: {
+  message: string;
+  onKeep: () => void;
+  onDelete: () => void;
+}) {
+  return (
+    <AlertDialog defaultOpen>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>Unsaved work could not be recovered</AlertDialogTitle>
+          <AlertDialogDescription>{message}</AlertDialogDescription>
+          <AlertDialogDescription>
+            Kept files are listed here each time tcad starts, until they are deleted.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <AlertDialogFooter>
+          <AlertDialogCancel onClick={onDelete}>Delete files</AlertDialogCancel>
+          <AlertDialogAction onClick={onKeep}>Keep</AlertDialogAction>
         </AlertDialogFooter>
       </AlertDialogContent>
     </AlertDialog>
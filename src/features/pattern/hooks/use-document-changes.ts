@@ -0,0 +1,89 @@
+import type { QueryClient } from "@tanstack/react-query";
+import type { ChangeEvent, DocumentChanges } from "@/types/event";
+
+import { useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
+
+import { currentDocumentId } from "@/lib/invoke";
+import { assetKeys, checkpointKeys, historyKeys, patternKeys, seamKeys } from "@/lib/query-keys";
+import { useProjectStore } from "@/stores/project-store";
+
+const DOCUMENT_CHANGED_EVENT = "document-changed";
+
+// Seam checks and checkpoint comparisons read every piece and seam, so any change to
+// either makes them stale
+function invalidateWholeProjectQueries(queryClient: QueryClient) {
+  queryClient.invalidateQueries({ queryKey: seamKeys.all });
+  queryClient.invalidateQueries({ queryKey: checkpointKeys.compares() });
+}
+
+function applyChange(queryClient: QueryClient, change: ChangeEvent) {
+  switch (change.type) {
+    case "PieceCreated":
+      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
+      invalidateWholeProjectQueries(queryClient);
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "PieceUpdated":
+      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
+      queryClient.invalidateQueries({ queryKey: patternKeys.detail(change.id) });
+      queryClient.invalidateQueries({ queryKey: patternKeys.area(change.id) });
+      queryClient.invalidateQueries({ queryKey: patternKeys.validation(change.id) });
+      queryClient.invalidateQueries({ queryKey: patternKeys.seamAllowances(change.id) });
+      invalidateWholeProjectQueries(queryClient);
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "PieceDeleted":
+      queryClient.removeQueries({ queryKey: patternKeys.detail(change.id) });
+      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
+      invalidateWholeProjectQueries(queryClient);
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "SeamCreated":
+    case "SeamUpdated":
+    case "SeamDeleted":
+      // Piece validation includes the seams on each piece
+      queryClient.invalidateQueries({ queryKey: patternKeys.validations() });
+      invalidateWholeProjectQueries(queryClient);
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "HistoryChanged":
+      queryClient.invalidateQueries({ queryKey: historyKeys.all });
+      break;
+    case "CheckpointsChanged":
+      queryClient.invalidateQueries({ queryKey: checkpointKeys.all });
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "AssetsChanged":
+      queryClient.invalidateQueries({ queryKey: assetKeys.lists() });
+      useProjectStore.getState().setDirty(true);
+      break;
+    case "ProjectLoaded":
+      queryClient.invalidateQueries({ queryKey: patternKeys.all });
+      queryClient.invalidateQueries({ queryKey: historyKeys.all });
+      queryClient.invalidateQueries({ queryKey: checkpointKeys.all });
+      queryClient.invalidateQueries({ queryKey: seamKeys.all });
+      queryClient.invalidateQueries({ queryKey: assetKeys.all });
+      break;
+  }
+}
+
+// Keep queries in step with the engine by refreshing whatever each change to this
+// window's document affects, including changes made from other windows
+export function useDocumentChanges() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<DocumentChanges>(DOCUMENT_CHANGED_EVENT, ({ payload }) => {
+      if (payload.document_id !== currentDocumentId())
+        return;
+      for (const change of payload.changes) {
+        applyChange(queryClient, change);
+      }
+    });
+    return () => {
+      void unlisten.then(fn => fn());
+    };
+  }, [queryClient]);
+}
@@ -1,10 +1,14 @@
+import type { AssetId, AssetKind } from "@/types/asset";
+import type { BatchOp } from "@/types/batch";
+import type { CheckpointId } from "@/types/checkpoint";
 import type { PatternPieceData, PatternPieceId } from "@/types/pattern";
+import type { Transform2D } from "@/types/transform";
 
 import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 
 import { resetPieceCounter } from "@/features/canvas/lib/piece-factory";
-import { canUndoRedo, createPatternPiece, deletePatternPiece, getAllPieces, getPiece, loadProject, newProject, redo, saveProject, undo, updatePatternPiece } from "@/lib/invoke";
-import { historyKeys, patternKeys } from "@/lib/query-keys";
+import { applyBatch, canUndoRedo, compareCheckpoint, createCheckpoint, createPatternPiece, deleteCheckpoint, deletePatternPiece, getAllPieces, getAssetData, getHistory, getPiece, importAsset, jumpToHistory, listAssets, listCheckpoints, loadProject, newProject, redo, removeAsset, restoreCheckpoint, saveProject, transformPiece, undo, updatePatternPiece, validateSeams } from "@/lib/invoke";
+import { assetKeys, checkpointKeys, historyKeys, patternKeys, seamKeys } from "@/lib/query-keys";
 import { useProjectStore } from "@/stores/project-store";
 
 export function usePatternPieces() {
@@ -22,6 +26,13 @@ export function usePatternPiece(id: PatternPieceId | undefined) {
   });
 }
 
+export function useSeamValidation(toleranceMm: number) {
+  return useQuery({
+    queryKey: seamKeys.validation(toleranceMm),
+    queryFn: () => validateSeams(toleranceMm),
+  });
+}
+
 export function useCanUndoRedo() {
   return useQuery({
     queryKey: historyKeys.canUndoRedo(),
@@ -29,91 +40,149 @@ export function useCanUndoRedo() {
   });
 }
 
+export function useHistory() {
+  return useQuery({
+    queryKey: historyKeys.list(),
+    queryFn: getHistory,
+  });
+}
+
 export function useCreatePiece() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: (piece: PatternPieceData) => createPatternPiece(piece),
-    onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
-      queryClient.invalidateQueries({ queryKey: historyKeys.all });
-      useProjectStore.getState().setDirty(true);
-    },
   });
 }
 
 export function useUpdatePiece() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: ({ id, piece }: { id: PatternPieceId; piece: PatternPieceData }) =>
       updatePatternPiece(id, piece),
-    onSuccess: (_data, { id }) => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.detail(id) });
-      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
-      queryClient.invalidateQueries({ queryKey: historyKeys.all });
-      useProjectStore.getState().setDirty(true);
-    },
+  });
+}
+
+export function useTransformPiece() {
+  return useMutation({
+    mutationFn: ({ id, transform }: { id: PatternPieceId; transform: Transform2D }) =>
+      transformPiece(id, transform),
   });
 }
 
 export function useDeletePiece() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: (id: PatternPieceId) => deletePatternPiece(id),
-    onSuccess: (_data, id) => {
-      queryClient.removeQueries({ queryKey: patternKeys.detail(id) });
-      queryClient.invalidateQueries({ queryKey: patternKeys.lists() });
-      queryClient.invalidateQueries({ queryKey: historyKeys.all });
-      useProjectStore.getState().setDirty(true);
-    },
+  });
+}
+
+export function useApplyBatch() {
+  return useMutation({
+    mutationFn: ({ ops, label }: { ops: BatchOp[]; label?: string }) => applyBatch(ops, label),
   });
 }
 
 export function useUndo() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: () => undo(),
-    onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.all });
-      queryClient.invalidateQueries({ queryKey: historyKeys.all });
-    },
   });
 }
 
 export function useRedo() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: () => redo(),
-    onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.all });
-      queryClient.invalidateQueries({ queryKey: historyKeys.all });
+  });
+}
+
+export function useJumpToHistory() {
+  return useMutation({
+    mutationFn: (index: number) => jumpToHistory(index),
+  });
+}
+
+export function useCheckpoints() {
+  return useQuery({
+    queryKey: checkpointKeys.lists(),
+    queryFn: listCheckpoints,
+  });
+}
+
+export function useCompareCheckpoint(id: CheckpointId | undefined) {
+  return useQuery({
+    queryKey: checkpointKeys.compare(id!),
+    queryFn: () => compareCheckpoint(id!),
+    enabled: !!id,
+  });
+}
+
+export function useCreateCheckpoint() {
+  return useMutation({
+    mutationFn: (name: string) => createCheckpoint(name),
+  });
+}
+
+export function useDeleteCheckpoint() {
+  return useMutation({
+    mutationFn: (id: CheckpointId) => deleteCheckpoint(id),
+  });
+}
+
+export function useRestoreCheckpoint() {
+  return useMutation({
+    mutationFn: (id: CheckpointId) => restoreCheckpoint(id),
+  });
+}
+
+export function useAssets() {
+  return useQuery({
+    queryKey: assetKeys.lists(),
+    queryFn: listAssets,
+  });
+}
+
+export function useAssetData(id: AssetId | undefined) {
+  return useQuery({
+    queryKey: assetKeys.data(id!),
+    queryFn: async () => new Uint8Array(await getAssetData(id!)),
+    enabled: !!id,
+  });
+}
+
+export function useImportAsset() {
+  return useMutation({
+    mutationFn: ({ kind, path }: { kind: AssetKind; path: string }) => importAsset(kind, path),
+  });
+}
+
+export function useRemoveAsset() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (id: AssetId) => removeAsset(id),
+    onSuccess: (_data, id) => {
+      queryClient.removeQueries({ queryKey: assetKeys.data(id) });
     },
   });
 }
 
 export function useSaveProject() {
   return useMutation({
-    mutationFn: (path: string) => saveProject(path),
+    mutationFn: (path: string) => {
+      const { historyDepth, backupCount } = useProjectStore.getState();
+      return saveProject(path, historyDepth, undefined, backupCount);
+    },
   });
 }
 
 export function useLoadProject() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: (path: string) => loadProject(path),
-    onSuccess: async () => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.all });
-      const pieces = await queryClient.fetchQuery({ queryKey: patternKeys.lists(), queryFn: getAllPieces });
+    onSuccess: (pieces) => {
       resetPieceCounter(pieces.length);
     },
   });
 }
 
 export function useNewProject() {
-  const queryClient = useQueryClient();
   return useMutation({
     mutationFn: () => newProject(),
     onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: patternKeys.all });
       resetPieceCounter();
     },
   });
@@ -5,19 +5,19 @@ export const piecePropertiesSchema = z.object({
   seam_allowance_mm: z.number().min(0, "Must be >= 0"),
   fabric_type: z.string().nullable(),
   cut_quantity: z.int().min(1, "Must be >= 1"),
-  mirror: z.boolean(),
+  fold_edge: z.int().min(0, "Must be >= 0").nullable(),
   notes: z.string(),
 });
 
 export type PiecePropertiesValues = z.infer<typeof piecePropertiesSchema>;
 
-export function extractProperties(piece: { name: string; seam_allowance_mm: number; metadata: { fabric_type: string | null; cut_quantity: number; mirror: boolean; notes: string } }): PiecePropertiesValues {
+export function extractProperties(piece: { name: string; seam_allowance_mm: number; fold_edge?: number | null; metadata: { fabric_type: string | null; cut_quantity: number; notes: string } }): PiecePropertiesValues {
   return {
     name: piece.name,
     seam_allowance_mm: piece.seam_allowance_mm,
     fabric_type: piece.metadata.fabric_type,
     cut_quantity: piece.metadata.cut_quantity,
-    mirror: piece.metadata.mirror,
+    fold_edge: piece.fold_edge ?? null,
     notes: piece.metadata.notes,
   };
 }
@@ -0,0 +1,45 @@
+import type { Point2D } from "@/types/pattern";
+import type { Transform2D } from "@/types/transform";
+
+// Builders for the transforms the engine applies to pieces; the geometry itself is
+// transformed in Rust.
+
+export function translate(dx: number, dy: number): Transform2D {
+  return { a: 1, b: 0, c: 0, d: 1, tx: dx, ty: dy };
+}
+
+// Applies `first`, then `second`
+export function compose(first: Transform2D, second: Transform2D): Transform2D {
+  return {
+    a: second.a * first.a + second.b * first.c,
+    b: second.a * first.b + second.b * first.d,
+    c: second.c * first.a + second.d * first.c,
+    d: second.c * first.b + second.d * first.d,
+    tx: second.a * first.tx + second.b * first.ty + second.tx,
+    ty: second.c * first.tx + second.d * first.ty + second.ty,
+  };
+}
+
+function about(pivot: Point2D, linear: Transform2D): Transform2D {
+  return compose(compose(translate(-pivot.x, -pivot.y), linear), translate(pivot.x, pivot.y));
+}
+
+export function rotateAbout(pivot: Point2D, angle: number): Transform2D {
+  const cos = Math.cos(angle);
+  const sin = Math.sin(angle);
+  return about(pivot, { a: cos, b: -sin, c: sin, d: cos, tx: 0, ty: 0 });
+}
+
+export function scaleAbout(pivot: Point2D, sx: number, sy: number = sx): Transform2D {
+  return about(pivot, { a: sx, b: 0, c: 0, d: sy, tx: 0, ty: 0 });
+}
+
+// Mirror across the vertical line through x
+export function mirrorXAbout(x: number): Transform2D {
+  return { a: -1, b: 0, c: 0, d: 1, tx: 2 * x, ty: 0 };
+}
+
+// Mirror across the horizontal line through y
+export function mirrorYAbout(y: number): Transform2D {
+  return { a: 1, b: 0, c: 0, d: -1, tx: 0, ty: 2 * y };
+}
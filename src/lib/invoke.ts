@@ -1,78 +1,171 @@
+import type { AssetId, AssetInfo, AssetKind } from "@/types/asset";
+import type { BatchOp } from "@/types/batch";
+import type { CheckpointDiff, CheckpointId, CheckpointInfo } from "@/types/checkpoint";
+import type { Diagnostic } from "@/types/diagnostic";
+import type { DocumentId } from "@/types/document";
+import type { AppError } from "@/types/error";
+import type { HistoryState } from "@/types/history";
+import type { PieceMeasurements } from "@/types/measurement";
 import type { MeshData } from "@/types/mesh";
 import type { PatternPieceData, PatternPieceId } from "@/types/pattern";
+import type { ProjectFormat, RecoveryStatus } from "@/types/project";
+import type { RepairReport } from "@/types/repair";
+import type { OutlineSection, Seam, SeamId, SeamLengthMatch } from "@/types/seam";
+import type { Transform2D } from "@/types/transform";
 
 import { invoke } from "@tauri-apps/api/core";
 
+// The document this window edits, set once by openDocument()
+let documentId: DocumentId | null = null;
+
+// Invoke a command on this window's document
+function invokeDoc<T>(cmd: string, args: Record<string, unknown> = {}): Promise<T> {
+  if (documentId === null)
+    return Promise.reject({ code: "Internal", message: "No document is open", context: {} } satisfies AppError);
+  return invoke(cmd, { ...args, documentId });
+}
+
+// Documents
+export function currentDocumentId(): DocumentId | null {
+  return documentId;
+}
+
+export async function openDocument(): Promise<DocumentId> {
+  documentId = await invoke<DocumentId>("open_document");
+  return documentId;
+}
+
+export function closeDocument(): Promise<void> {
+  if (documentId === null)
+    return Promise.resolve();
+  const id = documentId;
+  documentId = null;
+  return invoke("close_document", { documentId: id });
+}
+
+// Copies pieces, and the seams between them, from this window's document into another
+// as one undo step there; returns the new piece ids
+export function copyPieces(toDocumentId: DocumentId, pieceIds: PatternPieceId[]): Promise<PatternPieceId[]> {
+  if (documentId === null)
+    return Promise.reject({ code: "Internal", message: "No document is open", context: {} } satisfies AppError);
+  return invoke("copy_pieces", { fromDocumentId: documentId, toDocumentId, pieceIds });
+}
+
 export function createPatternPiece(piece: PatternPieceData): Promise<PatternPieceId> {
-  return invoke("create_pattern_piece", { piece });
+  return invokeDoc("create_pattern_piece", { piece });
 }
 
 export function updatePatternPiece(id: PatternPieceId, piece: PatternPieceData): Promise<void> {
-  return invoke("update_pattern_piece", { id, piece });
+  return invokeDoc("update_pattern_piece", { id, piece });
 }
 
 export function deletePatternPiece(id: PatternPieceId): Promise<void> {
-  return invoke("delete_pattern_piece", { id });
+  return invokeDoc("delete_pattern_piece", { id });
 }
 
 export function getAllPieces(): Promise<PatternPieceData[]> {
-  return invoke("get_all_pieces");
+  return invokeDoc("get_all_pieces");
 }
 
 export function getPiece(id: PatternPieceId): Promise<PatternPieceData> {
-  return invoke("get_piece", { id });
+  return invokeDoc("get_piece", { id });
+}
+
+// Applied atomically as one undo step; returns the id each create operation assigned
+export function applyBatch(ops: BatchOp[], label?: string): Promise<(string | null)[]> {
+  return invokeDoc("apply_batch", { ops, label });
+}
+
+export function createSeam(seam: Seam): Promise<SeamId> {
+  return invokeDoc("create_seam", { seam });
+}
+
+export function updateSeam(id: SeamId, seam: Seam): Promise<void> {
+  return invokeDoc("update_seam", { id, seam });
+}
+
+export function deleteSeam(id: SeamId): Promise<void> {
+  return invokeDoc("delete_seam", { id });
+}
+
+export function getAllSeams(): Promise<Seam[]> {
+  return invokeDoc("get_all_seams");
+}
+
+export function validateSeams(toleranceMm: number): Promise<Diagnostic[]> {
+  return invokeDoc("validate_seams", { toleranceMm });
 }
 
 export function computeSeamAllowance(pieceId: PatternPieceId, allowanceMm: number): Promise<PatternPieceData> {
-  return invoke("compute_seam_allowance", { pieceId, allowanceMm });
+  return invokeDoc("compute_seam_allowance", { pieceId, allowanceMm });
 }
 
-export function validatePieceGeometry(pieceId: PatternPieceId): Promise<string[]> {
-  return invoke("validate_piece_geometry", { pieceId });
+export function validatePieceGeometry(pieceId: PatternPieceId): Promise<Diagnostic[]> {
+  return invokeDoc("validate_piece_geometry", { pieceId });
 }
 
 export function computePieceArea(pieceId: PatternPieceId): Promise<number> {
-  return invoke("compute_piece_area", { pieceId });
+  return invokeDoc("compute_piece_area", { pieceId });
+}
+
+export function measurePiece(pieceId: PatternPieceId): Promise<PieceMeasurements> {
+  return invokeDoc("measure_piece", { pieceId });
+}
+
+export function matchSeamLengths(a: OutlineSection, b: OutlineSection, notchCount: number): Promise<SeamLengthMatch> {
+  return invokeDoc("match_seam_lengths", { a, b, notchCount });
+}
+
+export function repairPiece(pieceId: PatternPieceId, toleranceMm: number): Promise<RepairReport> {
+  return invokeDoc("repair_piece", { pieceId, toleranceMm });
+}
+
+export function transformPiece(pieceId: PatternPieceId, transform: Transform2D): Promise<PatternPieceData> {
+  return invokeDoc("transform_piece", { pieceId, transform });
 }
 
 export function generate3dMesh(pieceIds: PatternPieceId[]): Promise<MeshData> {
-  return invoke("generate_3d_mesh", { pieceIds });
+  return invokeDoc("generate_3d_mesh", { pieceIds });
 }
 
 export function exportSvg(pieceIds: PatternPieceId[]): Promise<string> {
-  return invoke("export_svg", { pieceIds });
+  return invokeDoc("export_svg", { pieceIds });
 }
 
 export function exportSvgToFile(pieceIds: PatternPieceId[], path: string): Promise<void> {
-  return invoke("export_svg_to_file", { pieceIds, path });
+  return invokeDoc("export_svg_to_file", { pieceIds, path });
 }
 
 export function exportDxf(pieceIds: PatternPieceId[], path: string): Promise<void> {
-  return invoke("export_dxf", { pieceIds, path });
+  return invokeDoc("export_dxf", { pieceIds, path });
 }
 
 export function exportPdf(pieceIds: PatternPieceId[], path: string, paperSize: string = "a4"): Promise<void> {
-  return invoke("export_pdf", { pieceIds, path, paperSize });
+  return invokeDoc("export_pdf", { pieceIds, path, paperSize });
 }
 
 // Project
-export function saveProject(path: string): Promise<void> {
-  return invoke("save_project", { path });
+// Saves up to `historyDepth` undo steps with the project (none for 0), keeping
+// `backupCount` previous versions beside it. Without a format, the project is bundled
+// only if it has embedded assets.
+export function saveProject(path: string, historyDepth?: number, format?: ProjectFormat, backupCount?: number): Promise<void> {
+  return invokeDoc("save_project", { path, historyDepth, format, backupCount });
 }
 
 export function loadProject(path: string): Promise<PatternPieceData[]> {
-  return invoke("load_project", { path });
+  return invokeDoc("load_project", { path });
 }
 
 export function newProject(): Promise<void> {
-  return invoke("new_project");
+  return invokeDoc("new_project");
 }
 
+// Recovery snapshots always keep recent undo steps, whatever the project setting is
 export function saveRecovery(): Promise<void> {
-  return invoke("save_recovery");
+  return invokeDoc("save_recovery");
 }
 
-export function checkRecovery(): Promise<PatternPieceData[] | null> {
+export function checkRecovery(): Promise<RecoveryStatus> {
   return invoke("check_recovery");
 }
 
@@ -80,19 +173,74 @@ export function clearRecovery(): Promise<void> {
   return invoke("clear_recovery");
 }
 
-export function restoreRecovery(): Promise<PatternPieceData[]> {
-  return invoke("restore_recovery");
+// Unreadable snapshots are reported by every check until they are deleted
+export function deleteUnreadableRecovery(): Promise<void> {
+  return invoke("delete_unreadable_recovery");
+}
+
+// Restores the given snapshot, or the newest, then deletes all snapshots
+export function restoreRecovery(id?: string): Promise<PatternPieceData[]> {
+  return invokeDoc("restore_recovery", { id });
+}
+
+// Embedded assets, saved in the project bundle
+export function importAsset(kind: AssetKind, path: string): Promise<AssetInfo> {
+  return invokeDoc("import_asset", { kind, path });
+}
+
+export function listAssets(): Promise<AssetInfo[]> {
+  return invokeDoc("list_assets");
+}
+
+// Raw bytes, not JSON
+export function getAssetData(id: AssetId): Promise<ArrayBuffer> {
+  return invokeDoc("get_asset_data", { id });
+}
+
+export function removeAsset(id: AssetId): Promise<void> {
+  return invokeDoc("remove_asset", { id });
+}
+
+// Checkpoints
+export function createCheckpoint(name: string): Promise<CheckpointId> {
+  return invokeDoc("create_checkpoint", { name });
+}
+
+export function listCheckpoints(): Promise<CheckpointInfo[]> {
+  return invokeDoc("list_checkpoints");
+}
+
+export function deleteCheckpoint(id: CheckpointId): Promise<void> {
+  return invokeDoc("delete_checkpoint", { id });
+}
+
+export function compareCheckpoint(id: CheckpointId): Promise<CheckpointDiff> {
+  return invokeDoc("compare_checkpoint", { id });
+}
+
+// Undoable, like any other edit
+export function restoreCheckpoint(id: CheckpointId): Promise<void> {
+  return invokeDoc("restore_checkpoint", { id });
 }
 
 // History (undo/redo)
 export function undo(): Promise<void> {
-  return invoke("undo");
+  return invokeDoc("undo");
 }
 
 export function redo(): Promise<void> {
-  return invoke("redo");
+  return invokeDoc("redo");
 }
 
 export function canUndoRedo(): Promise<[boolean, boolean]> {
-  return invoke("can_undo_redo");
+  return invokeDoc("can_undo_redo");
+}
+
+export function getHistory(): Promise<HistoryState> {
+  return invokeDoc("get_history");
+}
+
+// Undo or redo until exactly the first `index` entries are applied
+export function jumpToHistory(index: number): Promise<void> {
+  return invokeDoc("jump_to_history", { index });
 }
@@ -1,3 +1,5 @@
+import type { AssetId } from "@/types/asset";
+import type { CheckpointId } from "@/types/checkpoint";
 import type { PatternPieceId } from "@/types/pattern";
 
 export const patternKeys = {
@@ -6,13 +8,34 @@ export const patternKeys = {
   details: () => [...patternKeys.all, "detail"] as const,
   detail: (id: PatternPieceId) => [...patternKeys.details(), id] as const,
   area: (id: PatternPieceId) => [...patternKeys.all, "area", id] as const,
-  validation: (id: PatternPieceId) => [...patternKeys.all, "validation", id] as const,
-  seamAllowance: (id: PatternPieceId, mm: number) => [...patternKeys.all, "seam-allowance", id, mm] as const,
+  validations: () => [...patternKeys.all, "validation"] as const,
+  validation: (id: PatternPieceId) => [...patternKeys.validations(), id] as const,
+  seamAllowances: (id: PatternPieceId) => [...patternKeys.all, "seam-allowance", id] as const,
+  seamAllowance: (id: PatternPieceId, mm: number) => [...patternKeys.seamAllowances(id), mm] as const,
+};
+
+export const seamKeys = {
+  all: ["seam"] as const,
+  validation: (toleranceMm: number) => [...seamKeys.all, "validation", toleranceMm] as const,
 };
 
 export const historyKeys = {
   all: ["history"] as const,
   canUndoRedo: () => [...historyKeys.all, "can-undo-redo"] as const,
+  list: () => [...historyKeys.all, "list"] as const,
+};
+
+export const assetKeys = {
+  all: ["asset"] as const,
+  lists: () => [...assetKeys.all, "list"] as const,
+  data: (id: AssetId) => [...assetKeys.all, "data", id] as const,
+};
+
+export const checkpointKeys = {
+  all: ["checkpoint"] as const,
+  lists: () => [...checkpointKeys.all, "list"] as const,
+  compares: () => [...checkpointKeys.all, "compare"] as const,
+  compare: (id: CheckpointId) => [...checkpointKeys.compares(), id] as const,
 };
 
 export const meshKeys = {
@@ -3,9 +3,30 @@ import { createRoot } from "react-dom/client";
 
 import App from "./app.tsx";
 import "./index.css";
+import { StartupError } from "@/components/startup-error";
+import { closeDocument, openDocument } from "@/lib/invoke";
+import { errorMessage } from "@/types/error";
 
-createRoot(document.getElementById("root")!).render(
-  <StrictMode>
-    <App />
-  </StrictMode>,
+const root = createRoot(document.getElementById("root")!);
+
+// Each window edits its own document, opened before anything queries the engine
+openDocument().then(
+  () => {
+    window.addEventListener("beforeunload", () => {
+      void closeDocument();
+    });
+
+    root.render(
+      <StrictMode>
+        <App />
+      </StrictMode>,
+    );
+  },
+  (error: unknown) => {
+    root.render(
+      <StrictMode>
+        <StartupError message={errorMessage(error)} />
+      </StrictMode>,
+    );
+  },
 );
@@ -1,9 +1,24 @@
 import { create } from "zustand";
 
+const HISTORY_DEPTH_KEY = "tcad-history-depth";
+const BACKUP_COUNT_KEY = "tcad-backup-count";
+
+// A saved whole-number setting, or the fallback if none is saved
+function storedCount(key: string, fallback: number): number {
+  const value = Number(localStorage.getItem(key) ?? Number.NaN);
+  return Number.isInteger(value) && value >= 0 ? value : fallback;
+}
+
 type ProjectStore = {
   name: string;
   path: string | null;
   dirty: boolean;
+  // Undo steps saved with the project file; 0 saves none
+  historyDepth: number;
+  setHistoryDepth: (depth: number) => void;
+  // Previous versions kept as .bak files beside the project on save
+  backupCount: number;
+  setBackupCount: (count: number) => void;
   setProject: (name: string, path: string) => void;
   setDirty: (dirty: boolean) => void;
   reset: () => void;
@@ -13,6 +28,16 @@ export const useProjectStore = create<ProjectStore>(set => ({
   name: "Untitled",
   path: null,
   dirty: false,
+  historyDepth: storedCount(HISTORY_DEPTH_KEY, 50),
+  setHistoryDepth: (historyDepth) => {
+    localStorage.setItem(HISTORY_DEPTH_KEY, String(historyDepth));
+    set({ historyDepth });
+  },
+  backupCount: storedCount(BACKUP_COUNT_KEY, 3),
+  setBackupCount: (backupCount) => {
+    localStorage.setItem(BACKUP_COUNT_KEY, String(backupCount));
+    set({ backupCount });
+  },
   setProject: (name, path) => set({ name, path, dirty: false }),
   setDirty: dirty => set({ dirty }),
   reset: () => set({ name: "Untitled", path: null, dirty: false }),
@@ -0,0 +1,11 @@
+export type AssetId = string;
+
+export type AssetKind = "FabricSwatch" | "ReferencePhoto" | "MeasurementTable" | "ExportPreset";
+
+export type AssetInfo = {
+  id: AssetId;
+  kind: AssetKind;
+  name: string;
+  media_type: string;
+  size_bytes: number;
+};
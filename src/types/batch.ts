@@ -0,0 +1,14 @@
+import type { PatternPieceData, PatternPieceId } from "@/types/pattern";
+import type { Seam, SeamId } from "@/types/seam";
+import type { Transform2D } from "@/types/transform";
+
+export type BatchOp
+  = { type: "CreatePiece"; piece: PatternPieceData }
+    | { type: "UpdatePiece"; id: PatternPieceId; piece: PatternPieceData }
+    | { type: "DeletePiece"; id: PatternPieceId }
+    | { type: "TransformPiece"; id: PatternPieceId; transform: Transform2D }
+    // A transformed copy of a stored piece; its seams are not copied
+    | { type: "DuplicatePiece"; id: PatternPieceId; name: string; transform: Transform2D }
+    | { type: "CreateSeam"; seam: Seam }
+    | { type: "UpdateSeam"; id: SeamId; seam: Seam }
+    | { type: "DeleteSeam"; id: SeamId };
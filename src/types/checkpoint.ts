@@ -0,0 +1,26 @@
+import type { PatternPieceId } from "@/types/pattern";
+
+export type CheckpointId = string;
+
+export type CheckpointInfo = {
+  id: CheckpointId;
+  name: string;
+  timestamp_ms: number;
+  piece_count: number;
+  seam_count: number;
+};
+
+export type PieceRef = {
+  id: PatternPieceId;
+  name: string;
+};
+
+// Changes from a checkpoint to the current state
+export type CheckpointDiff = {
+  added_pieces: PieceRef[];
+  removed_pieces: PieceRef[];
+  changed_pieces: PieceRef[];
+  added_seams: number;
+  removed_seams: number;
+  changed_seams: number;
+};
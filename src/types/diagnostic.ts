@@ -0,0 +1,26 @@
+import type { Point2D } from "@/types/pattern";
+import type { SeamId } from "@/types/seam";
+
+export type Severity = "Error" | "Warning";
+
+export type DiagnosticCode
+  = "EmptyOutline"
+    | "MissingName"
+    | "NegativeSeamAllowance"
+    | "NonFiniteCoordinate"
+    | "OpenOutline"
+    | "DisconnectedSegment"
+    | "DegenerateSegment"
+    | "SelfIntersection"
+    | "InvalidFoldEdge"
+    | "InvalidSeam"
+    | "SeamLengthMismatch";
+
+export type Diagnostic = {
+  severity: Severity;
+  code: DiagnosticCode;
+  message: string;
+  segment_index: number | null;
+  location: Point2D | null;
+  seam_id: SeamId | null;
+};
@@ -0,0 +1,2 @@
+// Identifies one open project; each window edits its own document
+export type DocumentId = string;
@@ -0,0 +1,39 @@
+import type { PatternPieceId } from "@/types/pattern";
+import type { SeamId } from "@/types/seam";
+
+export type AppErrorCode
+  = "NotFound"
+    | "InvalidGeometry"
+    | "InvalidInput"
+    | "ExportFailed"
+    | "Io"
+    | "InvalidProject"
+    | "Internal";
+
+// What an error refers to, where the engine knows it
+export type ErrorContext = {
+  piece_id?: PatternPieceId;
+  seam_id?: SeamId;
+  path?: string;
+  segment?: number;
+  // Index of the failing operation within a batch
+  operation?: number;
+};
+
+// The error every command rejects with
+export type AppError = {
+  code: AppErrorCode;
+  message: string;
+  context: ErrorContext;
+};
+
+export function isAppError(value: unknown): value is AppError {
+  return typeof value === "object" && value !== null
+    && "code" in value && "message" in value && "context" in value;
+}
+
+export function errorMessage(error: unknown): string {
+  if (isAppError(error) || error instanceof Error)
+    return error.message;
+  return String(error);
+}
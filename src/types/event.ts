@@ -0,0 +1,21 @@
+import type { PatternPieceId } from "@/types/pattern";
+import type { SeamId } from "@/types/seam";
+
+export type ChangeEvent
+  = { type: "PieceCreated"; id: PatternPieceId }
+    | { type: "PieceUpdated"; id: PatternPieceId }
+    | { type: "PieceDeleted"; id: PatternPieceId }
+    | { type: "SeamCreated"; id: SeamId }
+    | { type: "SeamUpdated"; id: SeamId }
+    | { type: "SeamDeleted"; id: SeamId }
+    | { type: "HistoryChanged" }
+    | { type: "CheckpointsChanged" }
+    | { type: "AssetsChanged" }
+    // Everything was replaced by loading, recovering or starting a new project
+    | { type: "ProjectLoaded" };
+
+// The changes one command made to a document, in order
+export type DocumentChanges = {
+  document_id: string;
+  changes: ChangeEvent[];
+};
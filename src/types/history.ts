@@ -0,0 +1,11 @@
+export type HistoryEntry = {
+  label: string;
+  timestamp_ms: number;
+};
+
+export type HistoryState = {
+  // Oldest first: undoable steps, then redoable ones
+  entries: HistoryEntry[];
+  // How many of `entries` are applied
+  position: number;
+};
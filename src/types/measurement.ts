@@ -0,0 +1,11 @@
+export type PathLength = {
+  segments: number[];
+  closing: number;
+  total: number;
+};
+
+export type PieceMeasurements = {
+  perimeter: PathLength;
+  internal_lines: PathLength[];
+  cut_outs: PathLength[];
+};
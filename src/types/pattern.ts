@@ -5,8 +5,15 @@ export type Point2D = {
   y: number;
 };
 
+export type CornerStyle = "Miter" | "Square" | "Bevel" | "HemIncoming" | "HemOutgoing";
+
+export type EdgeAttributes = {
+  seam_allowance_mm?: number;
+  corner?: CornerStyle;
+};
+
 export type CurveSegment
-  = { type: "Line"; end: Point2D }
+  = ({ type: "Line"; end: Point2D }
     | { type: "QuadraticBezier"; control: Point2D; end: Point2D }
     | { type: "CubicBezier"; control1: Point2D; control2: Point2D; end: Point2D }
     | {
@@ -15,12 +22,40 @@ export type CurveSegment
       radius: number;
       start_angle: number;
       end_angle: number;
-    };
+    }) & EdgeAttributes;
+
+export type OutlinePosition = {
+  segment: number;
+  t: number;
+};
+
+export type NotchKind = "Slit" | "T" | "V" | "Castle" | "U";
+
+export type Notch = {
+  position: OutlinePosition;
+  kind: NotchKind;
+  depth_mm: number;
+  width_mm: number;
+  angle?: number;
+};
+
+export type MarkingKind = "DrillHole" | "Button" | "Buttonhole" | "FoldLine" | "PlaceOnFold";
+
+export type Marking = {
+  kind: MarkingKind;
+  position: Point2D;
+  size_mm: number;
+  angle?: number;
+};
+
+export type CutOut = {
+  origin: Point2D;
+  outline: CurveSegment[];
+};
 
 export type PieceMetadata = {
   fabric_type: string | null;
   cut_quantity: number;
-  mirror: boolean;
   notes: string;
 };
 
@@ -31,7 +66,10 @@ export type PatternPieceData = {
   outline: CurveSegment[];
   grain_line: [Point2D, Point2D] | null;
   seam_allowance_mm: number;
-  notches: Point2D[];
+  notches: Notch[];
   internal_lines: CurveSegment[][];
+  cut_outs: CutOut[];
+  markings: Marking[];
+  fold_edge?: number | null;
   metadata: PieceMetadata;
 };
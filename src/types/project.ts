@@ -0,0 +1,16 @@
+export type ProjectFormat = "Json" | "Bundle";
+
+export type RecoverySnapshot = {
+  id: string;
+  timestamp_ms: number;
+  piece_count: number;
+};
+
+// What a previous session that didn't close cleanly left behind
+export type RecoveryStatus = {
+  // Newest first
+  snapshots: RecoverySnapshot[];
+  // Unreadable snapshots, each message saying where the file was kept. They are listed
+  // on every check until deleted
+  errors: string[];
+};
@@ -0,0 +1,20 @@
+import type { PatternPieceData, Point2D } from "@/types/pattern";
+
+export type RepairKind
+  = "ClosedGap"
+    | "RemovedDegenerateSegment"
+    | "MergedCollinearLines"
+    | "RemovedLoop"
+    | "ReversedWinding"
+    | "ClearedFoldEdge";
+
+export type RepairChange = {
+  kind: RepairKind;
+  message: string;
+  location: Point2D | null;
+};
+
+export type RepairReport = {
+  piece: PatternPieceData;
+  changes: RepairChange[];
+};
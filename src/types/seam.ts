@@ -0,0 +1,40 @@
+import type { OutlinePosition, PatternPieceId, Point2D } from "@/types/pattern";
+
+export type OutlineSection = {
+  piece_id: PatternPieceId;
+  start: OutlinePosition;
+  end: OutlinePosition;
+  reversed?: boolean;
+};
+
+export type SeamId = string;
+
+export type SeamType = "Plain" | "French" | "FlatFelled" | "Overlocked" | "Bound";
+
+// Sections A and B are walked side by side, each in its own walking direction;
+// sections sewn end to start set `reversed` on section B
+export type Seam = {
+  id: SeamId;
+  a: OutlineSection;
+  b: OutlineSection;
+  seam_type: SeamType;
+  ease_mm: number;
+};
+
+export type NotchPlacement = {
+  position: OutlinePosition;
+  point: Point2D;
+};
+
+export type NotchPair = {
+  a: NotchPlacement;
+  b: NotchPlacement;
+};
+
+export type SeamLengthMatch = {
+  length_a: number;
+  length_b: number;
+  difference: number;
+  ease_percent: number;
+  notches: NotchPair[];
+};
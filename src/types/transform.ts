@@ -0,0 +1,9 @@
+// Row-major affine matrix [[a, b, tx], [c, d, ty], [0, 0, 1]]
+export type Transform2D = {
+  a: number;
+  b: number;
+  c: number;
+  d: number;
+  tx: number;
+  ty: number;
+};